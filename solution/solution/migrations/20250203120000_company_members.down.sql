@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS company_members_company_id_idx;
+
+DROP TABLE IF EXISTS company_members;
+
+DROP TYPE IF EXISTS company_role;
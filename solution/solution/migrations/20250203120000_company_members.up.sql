@@ -0,0 +1,16 @@
+CREATE TYPE company_role AS ENUM ('OWNER', 'MANAGER', 'ANALYST');
+
+CREATE TABLE IF NOT EXISTS company_members
+(
+    id            uuid         NOT NULL PRIMARY KEY,
+    company_id    uuid         NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+    email         text         NOT NULL UNIQUE,
+    name          text,
+    role          company_role NOT NULL,
+    password_hash text,
+    invite_hash   text,
+    invited_at    timestamptz  NOT NULL,
+    joined_at     timestamptz
+);
+
+CREATE INDEX IF NOT EXISTS company_members_company_id_idx ON company_members (company_id);
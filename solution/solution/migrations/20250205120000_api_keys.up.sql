@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS api_keys
+(
+    id         uuid        NOT NULL PRIMARY KEY,
+    company_id uuid        NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+    name       text        NOT NULL,
+    key_hash   text        NOT NULL,
+    scopes     text[]      NOT NULL,
+    created_at timestamptz NOT NULL,
+    expires_at timestamptz
+);
+
+CREATE INDEX IF NOT EXISTS api_keys_company_id_idx ON api_keys (company_id);
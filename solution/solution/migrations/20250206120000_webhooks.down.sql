@@ -0,0 +1,18 @@
+DROP TRIGGER IF EXISTS webhook_watcher_comments ON comments;
+DROP TRIGGER IF EXISTS webhook_watcher_likes ON likes;
+DROP TRIGGER IF EXISTS webhook_watcher_activations ON activations;
+DROP TRIGGER IF EXISTS webhook_watcher_promos ON promos;
+
+DROP FUNCTION IF EXISTS promo_webhook_trigger();
+DROP FUNCTION IF EXISTS enqueue_webhook_event(uuid, text, jsonb);
+
+DROP INDEX IF EXISTS webhook_deliveries_pending_idx;
+DROP INDEX IF EXISTS webhook_deliveries_webhook_id_idx;
+
+DROP TABLE IF EXISTS webhook_deliveries;
+
+DROP TYPE IF EXISTS webhook_delivery_status;
+
+DROP INDEX IF EXISTS webhooks_company_id_idx;
+
+DROP TABLE IF EXISTS webhooks;
@@ -0,0 +1,121 @@
+CREATE TABLE IF NOT EXISTS webhooks
+(
+    id         uuid        NOT NULL PRIMARY KEY,
+    company_id uuid        NOT NULL REFERENCES companies (id) ON DELETE CASCADE,
+    url        text        NOT NULL,
+    secret     text        NOT NULL,
+    events     text[]      NOT NULL,
+    active     boolean     NOT NULL,
+    created_at timestamptz NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_company_id_idx ON webhooks (company_id);
+
+CREATE TYPE webhook_delivery_status AS ENUM ('PENDING', 'SUCCEEDED', 'FAILED');
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries
+(
+    id              uuid                    NOT NULL PRIMARY KEY,
+    webhook_id      uuid                    NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event           text                    NOT NULL,
+    payload         jsonb                   NOT NULL,
+    status          webhook_delivery_status NOT NULL,
+    attempts        integer                 NOT NULL,
+    response_status integer,
+    last_error      text,
+    created_at      timestamptz             NOT NULL,
+    next_attempt_at timestamptz             NOT NULL,
+    last_attempt_at timestamptz
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_webhook_id_idx ON webhook_deliveries (webhook_id, created_at);
+CREATE INDEX IF NOT EXISTS webhook_deliveries_pending_idx ON webhook_deliveries (next_attempt_at) WHERE status = 'PENDING';
+
+
+CREATE OR REPLACE FUNCTION enqueue_webhook_event(company uuid, event_name text, payload jsonb) RETURNS void AS
+$$
+BEGIN
+    INSERT INTO webhook_deliveries (id, webhook_id, event, payload, status, attempts, created_at, next_attempt_at)
+    SELECT gen_random_uuid(), webhooks.id, event_name, payload, 'PENDING', 0, now(), now()
+    FROM webhooks
+    WHERE webhooks.company_id = company
+      AND webhooks.active
+      AND event_name = ANY (webhooks.events);
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION promo_webhook_trigger()
+    RETURNS TRIGGER AS
+$$
+DECLARE
+    pr promos;
+BEGIN
+    IF TG_TABLE_NAME = 'promos' THEN
+        pr := NEW;
+
+        IF now() > pr.active_until THEN
+            PERFORM enqueue_webhook_event(pr.company_id, 'promo.expired',
+                                          jsonb_build_object('promo_id', pr.id,
+                                                             'active_until', pr.active_until));
+        ELSIF now() >= pr.active_from THEN
+            PERFORM enqueue_webhook_event(pr.company_id, 'promo.exhausted',
+                                          jsonb_build_object('promo_id', pr.id,
+                                                             'used_count', pr.used_count,
+                                                             'max_count', pr.max_count));
+        END IF;
+
+        RETURN NULL;
+    END IF;
+
+    SELECT *
+    INTO pr
+    FROM promos
+    WHERE id = NEW.promo_id;
+
+    IF TG_TABLE_NAME = 'activations' THEN
+        PERFORM enqueue_webhook_event(pr.company_id, 'promo.activated',
+                                      jsonb_build_object('promo_id', pr.id,
+                                                         'user_id', NEW.user_id,
+                                                         'promo', NEW.promo,
+                                                         'date', NEW.date));
+    ELSIF TG_TABLE_NAME = 'likes' THEN
+        PERFORM enqueue_webhook_event(pr.company_id, 'promo.liked',
+                                      jsonb_build_object('promo_id', pr.id,
+                                                         'user_id', NEW.user_id));
+    ELSIF TG_TABLE_NAME = 'comments' THEN
+        PERFORM enqueue_webhook_event(pr.company_id, 'promo.commented',
+                                      jsonb_build_object('promo_id', pr.id,
+                                                         'comment_id', NEW.id,
+                                                         'user_id', NEW.author_id,
+                                                         'text', NEW.text,
+                                                         'date', NEW.date));
+    END IF;
+
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER webhook_watcher_promos
+    AFTER UPDATE OF active
+    ON promos
+    FOR EACH ROW
+    WHEN (OLD.active AND NOT NEW.active)
+EXECUTE FUNCTION promo_webhook_trigger();
+
+CREATE TRIGGER webhook_watcher_activations
+    AFTER INSERT
+    ON activations
+    FOR EACH ROW
+EXECUTE FUNCTION promo_webhook_trigger();
+
+CREATE TRIGGER webhook_watcher_likes
+    AFTER INSERT
+    ON likes
+    FOR EACH ROW
+EXECUTE FUNCTION promo_webhook_trigger();
+
+CREATE TRIGGER webhook_watcher_comments
+    AFTER INSERT
+    ON comments
+    FOR EACH ROW
+EXECUTE FUNCTION promo_webhook_trigger();
@@ -0,0 +1,11 @@
+DROP TRIGGER IF EXISTS event_watcher_comments ON comments;
+DROP TRIGGER IF EXISTS event_watcher_likes ON likes;
+DROP TRIGGER IF EXISTS event_watcher_activations ON activations;
+
+DROP FUNCTION IF EXISTS record_event_trigger();
+DROP FUNCTION IF EXISTS record_event(text, uuid, jsonb);
+
+DROP INDEX IF EXISTS events_transaction_id_idx;
+DROP INDEX IF EXISTS events_created_at_idx;
+
+DROP TABLE IF EXISTS events;
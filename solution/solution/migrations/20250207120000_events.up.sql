@@ -0,0 +1,92 @@
+CREATE TABLE IF NOT EXISTS events
+(
+    id             bigserial   NOT NULL PRIMARY KEY,
+    -- Ids are taken when rows are inserted, not when they are committed, so
+    -- consumers follow the writing transaction's id instead (see get_after.sql)
+    transaction_id bigint      NOT NULL DEFAULT pg_current_xact_id()::text::bigint,
+    event          text        NOT NULL,
+    company_id     uuid        NOT NULL,
+    promo_id       uuid        NOT NULL,
+    payload        jsonb       NOT NULL,
+    created_at     timestamptz NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS events_created_at_idx ON events (created_at);
+CREATE INDEX IF NOT EXISTS events_transaction_id_idx ON events (transaction_id, id);
+
+
+CREATE OR REPLACE FUNCTION record_event(event_name text, promo uuid, payload jsonb) RETURNS void AS
+$$
+DECLARE
+    event_id bigint;
+BEGIN
+    INSERT INTO events (event, company_id, promo_id, payload, created_at)
+    SELECT event_name, promos.company_id, promos.id, payload, now()
+    FROM promos
+    WHERE promos.id = promo
+    RETURNING id INTO event_id;
+
+    IF event_id IS NOT NULL THEN
+        PERFORM pg_notify('events', event_id::text);
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION record_event_trigger()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_TABLE_NAME = 'activations' THEN
+        PERFORM record_event('promo.activated', NEW.promo_id,
+                             jsonb_build_object('user_id', NEW.user_id,
+                                                'promo', NEW.promo,
+                                                'date', NEW.date));
+    ELSIF TG_TABLE_NAME = 'likes' THEN
+        IF TG_OP = 'INSERT' THEN
+            PERFORM record_event('promo.liked', NEW.promo_id,
+                                 jsonb_build_object('user_id', NEW.user_id));
+        ELSIF TG_OP = 'DELETE' THEN
+            PERFORM record_event('promo.unliked', OLD.promo_id,
+                                 jsonb_build_object('user_id', OLD.user_id));
+        END IF;
+    ELSIF TG_TABLE_NAME = 'comments' THEN
+        IF TG_OP = 'INSERT' THEN
+            PERFORM record_event('comment.created', NEW.promo_id,
+                                 jsonb_build_object('comment_id', NEW.id,
+                                                    'user_id', NEW.author_id,
+                                                    'text', NEW.text,
+                                                    'date', NEW.date));
+        ELSIF TG_OP = 'UPDATE' THEN
+            PERFORM record_event('comment.updated', NEW.promo_id,
+                                 jsonb_build_object('comment_id', NEW.id,
+                                                    'user_id', NEW.author_id,
+                                                    'text', NEW.text,
+                                                    'date', NEW.date));
+        ELSIF TG_OP = 'DELETE' THEN
+            PERFORM record_event('comment.deleted', OLD.promo_id,
+                                 jsonb_build_object('comment_id', OLD.id,
+                                                    'user_id', OLD.author_id));
+        END IF;
+    END IF;
+
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER event_watcher_activations
+    AFTER INSERT
+    ON activations
+    FOR EACH ROW
+EXECUTE FUNCTION record_event_trigger();
+
+CREATE TRIGGER event_watcher_likes
+    AFTER INSERT OR DELETE
+    ON likes
+    FOR EACH ROW
+EXECUTE FUNCTION record_event_trigger();
+
+CREATE TRIGGER event_watcher_comments
+    AFTER INSERT OR UPDATE OR DELETE
+    ON comments
+    FOR EACH ROW
+EXECUTE FUNCTION record_event_trigger();
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS promo_views;
+
+DROP TYPE IF EXISTS promo_view_kind;
@@ -0,0 +1,10 @@
+CREATE TYPE promo_view_kind AS ENUM ('IMPRESSION', 'VIEW');
+
+CREATE TABLE IF NOT EXISTS promo_views
+(
+    promo_id uuid            NOT NULL REFERENCES promos (id) ON DELETE CASCADE,
+    user_id  uuid            NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    kind     promo_view_kind NOT NULL,
+    day      date            NOT NULL,
+    PRIMARY KEY (promo_id, kind, day, user_id)
+);
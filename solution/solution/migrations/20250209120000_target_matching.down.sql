@@ -0,0 +1 @@
+DROP FUNCTION IF EXISTS target_matches(target, user_target_settings);
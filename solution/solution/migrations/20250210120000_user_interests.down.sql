@@ -0,0 +1,13 @@
+DROP FUNCTION IF EXISTS target_matches(target, user_target_settings, text[]);
+
+CREATE OR REPLACE FUNCTION target_matches(t target, settings user_target_settings) RETURNS boolean AS
+$$
+SELECT (t.country IS NULL OR lower(t.country) = lower(settings.country))
+           AND (t.age_from IS NULL OR t.age_from <= settings.age)
+           AND (t.age_to IS NULL OR t.age_to >= settings.age)
+$$ LANGUAGE sql IMMUTABLE;
+
+DROP FUNCTION IF EXISTS user_interests(uuid);
+
+ALTER TABLE users
+    DROP COLUMN IF EXISTS interests;
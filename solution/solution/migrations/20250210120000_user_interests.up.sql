@@ -0,0 +1,32 @@
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS interests text[] NOT NULL DEFAULT '{}';
+
+CREATE OR REPLACE FUNCTION user_interests(uid uuid) RETURNS text[] AS
+$$
+SELECT coalesce(array_agg(DISTINCT lower(interest)), '{}')
+FROM (SELECT unnest(interests) AS interest
+      FROM users
+      WHERE id = uid
+      UNION ALL
+      SELECT unnest((promos.target).categories)
+      FROM likes
+               JOIN promos ON promos.id = likes.promo_id
+      WHERE likes.user_id = uid
+      UNION ALL
+      SELECT unnest((promos.target).categories)
+      FROM activations
+               JOIN promos ON promos.id = activations.promo_id
+      WHERE activations.user_id = uid) AS user_interests
+$$ LANGUAGE sql STABLE;
+
+DROP FUNCTION IF EXISTS target_matches(target, user_target_settings);
+
+CREATE OR REPLACE FUNCTION target_matches(t target, settings user_target_settings, interests text[]) RETURNS boolean AS
+$$
+SELECT (t.country IS NULL OR lower(t.country) = lower(settings.country))
+           AND (t.age_from IS NULL OR t.age_from <= settings.age)
+           AND (t.age_to IS NULL OR t.age_to >= settings.age)
+           AND (coalesce(cardinality(t.categories), 0) = 0
+        OR coalesce(cardinality(interests), 0) = 0
+        OR lower(t.categories::text)::text[] && interests)
+$$ LANGUAGE sql IMMUTABLE;
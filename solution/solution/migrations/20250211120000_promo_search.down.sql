@@ -0,0 +1,12 @@
+DROP INDEX IF EXISTS promos_search_vector_idx;
+
+DROP TRIGGER IF EXISTS company_promos_search_vector_trigger ON companies;
+DROP FUNCTION IF EXISTS update_company_promos_search_vector();
+
+DROP TRIGGER IF EXISTS promo_search_vector_trigger ON promos;
+DROP FUNCTION IF EXISTS update_promo_search_vector();
+
+DROP FUNCTION IF EXISTS promo_search_vector(text, text);
+
+ALTER TABLE promos
+    DROP COLUMN IF EXISTS search_vector;
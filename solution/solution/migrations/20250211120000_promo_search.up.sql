@@ -0,0 +1,49 @@
+ALTER TABLE promos
+    ADD COLUMN IF NOT EXISTS search_vector tsvector NOT NULL DEFAULT ''::tsvector;
+
+CREATE OR REPLACE FUNCTION promo_search_vector(description text, company_name text) RETURNS tsvector AS
+$$
+SELECT setweight(to_tsvector('simple', coalesce(description, '')), 'A') ||
+       setweight(to_tsvector('simple', coalesce(company_name, '')), 'B')
+$$ LANGUAGE sql IMMUTABLE;
+
+CREATE OR REPLACE FUNCTION update_promo_search_vector() RETURNS trigger AS
+$$
+BEGIN
+    NEW.search_vector := promo_search_vector(
+            NEW.description,
+            (SELECT name FROM companies WHERE id = NEW.company_id)
+                         );
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER promo_search_vector_trigger
+    BEFORE INSERT OR UPDATE OF description, company_id
+    ON promos
+    FOR EACH ROW
+EXECUTE FUNCTION update_promo_search_vector();
+
+CREATE OR REPLACE FUNCTION update_company_promos_search_vector() RETURNS trigger AS
+$$
+BEGIN
+    UPDATE promos
+    SET search_vector = promo_search_vector(description, NEW.name)
+    WHERE company_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER company_promos_search_vector_trigger
+    AFTER UPDATE OF name
+    ON companies
+    FOR EACH ROW
+    WHEN (OLD.name IS DISTINCT FROM NEW.name)
+EXECUTE FUNCTION update_company_promos_search_vector();
+
+UPDATE promos
+SET search_vector = promo_search_vector(description, companies.name)
+FROM companies
+WHERE companies.id = promos.company_id;
+
+CREATE INDEX IF NOT EXISTS promos_search_vector_idx ON promos USING GIN (search_vector);
@@ -0,0 +1,8 @@
+DROP TRIGGER IF EXISTS promo_schedule_trigger ON promos;
+DROP FUNCTION IF EXISTS notify_promo_schedule();
+
+DROP FUNCTION IF EXISTS update_due_promos_active(timestamptz);
+DROP FUNCTION IF EXISTS next_promo_transition();
+
+DROP INDEX IF EXISTS promos_active_until_idx;
+DROP INDEX IF EXISTS promos_active_from_idx;
@@ -0,0 +1,55 @@
+CREATE INDEX IF NOT EXISTS promos_active_from_idx ON promos (active_from);
+CREATE INDEX IF NOT EXISTS promos_active_until_idx ON promos (active_until);
+
+-- `active_until` is inclusive, so a promo only expires right after it.
+CREATE OR REPLACE FUNCTION next_promo_transition() RETURNS timestamptz AS
+$$
+SELECT least(
+               (SELECT min(active_from) FROM promos WHERE active_from > now()),
+               (SELECT min(active_until) FROM promos WHERE active_until >= now()) + interval '1 microsecond'
+       )
+$$ LANGUAGE sql STABLE;
+
+-- Only promos whose activity period started or ended after `since` can change
+-- here, status and capacity changes are applied by triggers. Without `since`
+-- every promo is reconciled once, e.g. after a restart. Returns the time the
+-- promos were checked at, to be passed as `since` on the next run.
+CREATE OR REPLACE FUNCTION update_due_promos_active(since timestamptz) RETURNS timestamptz AS
+$$
+DECLARE
+    pr promos;
+BEGIN
+    IF since IS NULL THEN
+        FOR pr IN SELECT *
+                  FROM promos
+                  WHERE active IS DISTINCT FROM (active_from <= now() AND now() <= active_until)
+            LOOP
+                PERFORM update_promo_active(pr);
+            END LOOP;
+    ELSE
+        FOR pr IN SELECT *
+                  FROM promos
+                  WHERE (active_from > since AND active_from <= now())
+                     OR (active_until >= since AND active_until < now())
+            LOOP
+                PERFORM update_promo_active(pr);
+            END LOOP;
+    END IF;
+
+    RETURN now();
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION notify_promo_schedule() RETURNS trigger AS
+$$
+BEGIN
+    PERFORM pg_notify('promo_schedule', NEW.id::text);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER promo_schedule_trigger
+    AFTER INSERT OR UPDATE OF active_from, active_until
+    ON promos
+    FOR EACH ROW
+EXECUTE FUNCTION notify_promo_schedule();
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS activations_promo_date_idx;
+
+ALTER TABLE promos
+    DROP COLUMN IF EXISTS max_per_day,
+    DROP COLUMN IF EXISTS cooldown,
+    DROP COLUMN IF EXISTS per_user_limit;
@@ -0,0 +1,6 @@
+ALTER TABLE promos
+    ADD COLUMN IF NOT EXISTS per_user_limit integer,
+    ADD COLUMN IF NOT EXISTS cooldown       integer,
+    ADD COLUMN IF NOT EXISTS max_per_day    integer;
+
+CREATE INDEX IF NOT EXISTS activations_promo_date_idx ON activations (promo_id, date);
@@ -0,0 +1,43 @@
+ALTER TABLE promos
+    ADD COLUMN IF NOT EXISTS promo_unique text[] NOT NULL DEFAULT '{}';
+
+UPDATE promos
+SET promo_unique = coalesce((SELECT array_agg(code ORDER BY position)
+                             FROM promo_codes
+                             WHERE promo_id = promos.id), '{}');
+
+DROP TRIGGER IF EXISTS active_watcher_promo_codes ON promo_codes;
+DROP FUNCTION IF EXISTS update_promo_codes_active();
+
+CREATE OR REPLACE FUNCTION update_promo_active(pr promos) RETURNS void AS
+$$
+DECLARE
+    timestamp      timestamptz := now();
+    is_active      bool        := true;
+    current_active bool;
+BEGIN
+    is_active := is_active AND (pr.active_from <= timestamp AND timestamp <= pr.active_until);
+
+    IF pr.mode = 'COMMON' THEN
+        is_active := is_active AND (pr.used_count < pr.max_count);
+    ELSIF pr.mode = 'UNIQUE' THEN
+        is_active := is_active AND (pr.used_count < array_length(pr.promo_unique, 1));
+    END IF;
+
+    SELECT promos.active
+    INTO current_active
+    FROM promos
+    WHERE id = pr.id;
+
+    IF current_active IS DISTINCT FROM is_active THEN
+        UPDATE promos
+        SET active = is_active
+        WHERE id = pr.id;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP FUNCTION IF EXISTS promo_code_count(uuid, promo_code_status);
+
+DROP TABLE IF EXISTS promo_codes;
+DROP TYPE IF EXISTS promo_code_status;
@@ -0,0 +1,107 @@
+CREATE TYPE promo_code_status AS ENUM ('AVAILABLE', 'ISSUED');
+
+CREATE TABLE IF NOT EXISTS promo_codes
+(
+    promo_id    uuid              NOT NULL REFERENCES promos (id) ON DELETE CASCADE,
+    code        text              NOT NULL,
+    position    bigint            NOT NULL,
+    status      promo_code_status NOT NULL DEFAULT 'AVAILABLE',
+    reserved_by uuid REFERENCES users (id) ON DELETE SET NULL,
+    issued_at   timestamptz,
+    PRIMARY KEY (promo_id, code)
+);
+
+CREATE INDEX IF NOT EXISTS promo_codes_available_idx
+    ON promo_codes (promo_id, position) WHERE status = 'AVAILABLE';
+
+CREATE INDEX IF NOT EXISTS promo_codes_position_idx
+    ON promo_codes (promo_id, position);
+
+-- A user can only ever hold one code of a promo.
+CREATE UNIQUE INDEX IF NOT EXISTS promo_codes_reserved_by_idx
+    ON promo_codes (promo_id, reserved_by);
+
+WITH codes AS (SELECT promos.id AS promo_id,
+                      unique_codes.code,
+                      unique_codes.position,
+                      unique_codes.position <= promos.used_count AS issued
+               FROM promos,
+                    unnest(promos.promo_unique) WITH ORDINALITY AS unique_codes(code, position)
+               WHERE promos.mode = 'UNIQUE'),
+     holders AS (SELECT DISTINCT ON (promo_id, user_id) promo_id, user_id, promo AS code, date
+                 FROM activations
+                 ORDER BY promo_id, user_id, date)
+INSERT
+INTO promo_codes (promo_id, code, position, status, reserved_by, issued_at)
+SELECT codes.promo_id,
+       codes.code,
+       codes.position,
+       CASE WHEN codes.issued THEN 'ISSUED'::promo_code_status ELSE 'AVAILABLE'::promo_code_status END,
+       holders.user_id,
+       holders.date
+FROM codes
+         LEFT JOIN holders ON holders.promo_id = codes.promo_id AND holders.code = codes.code AND codes.issued
+ON CONFLICT DO NOTHING;
+
+-- Counts a promo's codes, or only the ones in `status` when it's given.
+CREATE OR REPLACE FUNCTION promo_code_count(pid uuid, status promo_code_status DEFAULT NULL) RETURNS bigint AS
+$$
+SELECT count(*)
+FROM promo_codes
+WHERE promo_id = $1
+  AND ($2 IS NULL OR promo_codes.status = $2)
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION update_promo_active(pr promos) RETURNS void AS
+$$
+DECLARE
+    timestamp      timestamptz := now();
+    is_active      bool        := true;
+    current_active bool;
+BEGIN
+    is_active := is_active AND (pr.active_from <= timestamp AND timestamp <= pr.active_until);
+
+    IF pr.mode = 'COMMON' THEN
+        is_active := is_active AND (pr.used_count < pr.max_count);
+    ELSIF pr.mode = 'UNIQUE' THEN
+        is_active := is_active AND EXISTS (SELECT 1
+                                           FROM promo_codes
+                                           WHERE promo_id = pr.id
+                                             AND status = 'AVAILABLE');
+    END IF;
+
+    SELECT promos.active
+    INTO current_active
+    FROM promos
+    WHERE id = pr.id;
+
+    IF current_active IS DISTINCT FROM is_active THEN
+        UPDATE promos
+        SET active = is_active
+        WHERE id = pr.id;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION update_promo_codes_active() RETURNS trigger AS
+$$
+DECLARE
+    pr promos;
+BEGIN
+    FOR pr IN SELECT * FROM promos WHERE id IN (SELECT DISTINCT promo_id FROM new_codes)
+        LOOP
+            PERFORM update_promo_active(pr);
+        END LOOP;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE TRIGGER active_watcher_promo_codes
+    AFTER INSERT
+    ON promo_codes
+    REFERENCING NEW TABLE AS new_codes
+    FOR EACH STATEMENT
+EXECUTE FUNCTION update_promo_codes_active();
+
+ALTER TABLE promos
+    DROP COLUMN IF EXISTS promo_unique;
@@ -0,0 +1,7 @@
+DROP TRIGGER IF EXISTS event_watcher_redemptions ON activations;
+DROP FUNCTION IF EXISTS record_redemption_event_trigger();
+
+DROP INDEX IF EXISTS activations_promo_code_idx;
+
+ALTER TABLE activations
+    DROP COLUMN IF EXISTS redeemed_at;
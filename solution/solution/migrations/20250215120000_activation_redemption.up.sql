@@ -0,0 +1,23 @@
+ALTER TABLE activations
+    ADD COLUMN IF NOT EXISTS redeemed_at timestamptz;
+
+CREATE INDEX IF NOT EXISTS activations_promo_code_idx ON activations (promo, promo_id);
+
+CREATE OR REPLACE FUNCTION record_redemption_event_trigger()
+    RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM record_event('promo.redeemed', NEW.promo_id,
+                         jsonb_build_object('user_id', NEW.user_id,
+                                            'promo', NEW.promo,
+                                            'date', NEW.redeemed_at));
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER event_watcher_redemptions
+    AFTER UPDATE OF redeemed_at
+    ON activations
+    FOR EACH ROW
+    WHEN (OLD.redeemed_at IS NULL AND NEW.redeemed_at IS NOT NULL)
+EXECUTE FUNCTION record_redemption_event_trigger();
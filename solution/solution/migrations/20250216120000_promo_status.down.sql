@@ -0,0 +1,45 @@
+DROP TRIGGER IF EXISTS webhook_watcher_promos ON promos;
+CREATE TRIGGER webhook_watcher_promos
+    AFTER UPDATE OF active
+    ON promos
+    FOR EACH ROW
+    WHEN (OLD.active AND NOT NEW.active)
+EXECUTE FUNCTION promo_webhook_trigger();
+
+CREATE OR REPLACE FUNCTION update_promo_active(pr promos) RETURNS void AS
+$$
+DECLARE
+    timestamp      timestamptz := now();
+    is_active      bool        := true;
+    current_active bool;
+BEGIN
+    is_active := is_active AND (pr.active_from <= timestamp AND timestamp <= pr.active_until);
+
+    IF pr.mode = 'COMMON' THEN
+        is_active := is_active AND (pr.used_count < pr.max_count);
+    ELSIF pr.mode = 'UNIQUE' THEN
+        is_active := is_active AND EXISTS (SELECT 1
+                                           FROM promo_codes
+                                           WHERE promo_id = pr.id
+                                             AND status = 'AVAILABLE');
+    END IF;
+
+    SELECT promos.active
+    INTO current_active
+    FROM promos
+    WHERE id = pr.id;
+
+    IF current_active IS DISTINCT FROM is_active THEN
+        UPDATE promos
+        SET active = is_active
+        WHERE id = pr.id;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP INDEX IF EXISTS promos_status_idx;
+
+ALTER TABLE promos
+    DROP COLUMN IF EXISTS status;
+
+DROP TYPE IF EXISTS promo_status;
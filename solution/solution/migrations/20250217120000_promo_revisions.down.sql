@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS promo_revisions;
+DROP TYPE IF EXISTS revision_author;
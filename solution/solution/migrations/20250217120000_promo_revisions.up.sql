@@ -0,0 +1,15 @@
+CREATE TYPE revision_author AS ENUM ('COMPANY', 'MEMBER', 'INTEGRATION');
+
+CREATE TABLE IF NOT EXISTS promo_revisions
+(
+    id            uuid            NOT NULL PRIMARY KEY,
+    promo_id      uuid            NOT NULL REFERENCES promos (id) ON DELETE CASCADE,
+    revision      integer         NOT NULL,
+    author        revision_author NOT NULL,
+    member_id     uuid REFERENCES company_members (id) ON DELETE SET NULL,
+    restored_from integer,
+    changes       jsonb           NOT NULL,
+    snapshot      jsonb           NOT NULL,
+    created_at    timestamptz     NOT NULL,
+    UNIQUE (promo_id, revision)
+);
@@ -0,0 +1,4 @@
+SELECT id, company_id, name, key_hash, scopes, created_at, expires_at
+FROM api_keys
+WHERE company_id = $1
+ORDER BY created_at DESC
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, company_id, name, key_hash, scopes, created_at, expires_at
+FROM api_keys
+WHERE id = $1
\ No newline at end of file
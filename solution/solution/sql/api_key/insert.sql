@@ -0,0 +1,3 @@
+INSERT INTO api_keys (id, company_id, name, key_hash, scopes, created_at, expires_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+RETURNING id, company_id, name, key_hash, scopes, created_at, expires_at
\ No newline at end of file
@@ -9,5 +9,6 @@ SELECT comments.id,
 FROM comments
          LEFT JOIN users ON users.id = author_id
 WHERE promo_id = $1
-ORDER BY date DESC
+  AND ($4::timestamptz IS NULL OR (date, comments.id) < ($4, $5))
+ORDER BY date DESC, comments.id DESC
 LIMIT $2 OFFSET $3
\ No newline at end of file
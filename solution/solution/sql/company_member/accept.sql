@@ -0,0 +1,7 @@
+UPDATE company_members
+SET name          = $2,
+    password_hash = $3,
+    invite_hash   = NULL,
+    joined_at     = $4
+WHERE id = $1
+RETURNING id, company_id, email, name, role AS "role: DBCompanyRole", password_hash, invite_hash, invited_at, joined_at
\ No newline at end of file
@@ -0,0 +1,3 @@
+DELETE
+FROM company_members
+WHERE id = $1
\ No newline at end of file
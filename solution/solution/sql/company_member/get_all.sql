@@ -0,0 +1,4 @@
+SELECT id, company_id, email, name, role AS "role: DBCompanyRole", password_hash, invite_hash, invited_at, joined_at
+FROM company_members
+WHERE company_id = $1
+ORDER BY invited_at
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, company_id, email, name, role AS "role: DBCompanyRole", password_hash, invite_hash, invited_at, joined_at
+FROM company_members
+WHERE email = lower($1)
\ No newline at end of file
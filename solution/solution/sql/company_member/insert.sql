@@ -0,0 +1,3 @@
+INSERT INTO company_members (id, company_id, email, name, role, password_hash, invite_hash, invited_at, joined_at)
+VALUES ($1, $2, lower($3), $4, $5, $6, $7, $8, $9)
+RETURNING id, company_id, email, name, role AS "role: DBCompanyRole", password_hash, invite_hash, invited_at, joined_at
\ No newline at end of file
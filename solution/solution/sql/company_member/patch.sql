@@ -0,0 +1,4 @@
+UPDATE company_members
+SET role = $2
+WHERE id = $1
+RETURNING id, company_id, email, name, role AS "role: DBCompanyRole", password_hash, invite_hash, invited_at, joined_at
\ No newline at end of file
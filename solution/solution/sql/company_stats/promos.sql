@@ -0,0 +1,57 @@
+WITH company_promos AS (SELECT id, description, active
+                        FROM promos
+                        WHERE company_id = $1),
+     activation_counts AS (SELECT promo_id, count(*) AS count
+                           FROM activations
+                           WHERE promo_id IN (SELECT id FROM company_promos)
+                           GROUP BY promo_id),
+     like_counts AS (SELECT promo_id, count(*) AS count
+                     FROM likes
+                     WHERE promo_id IN (SELECT id FROM company_promos)
+                     GROUP BY promo_id),
+     comment_counts AS (SELECT promo_id, count(*) AS count
+                        FROM comments
+                        WHERE promo_id IN (SELECT id FROM company_promos)
+                        GROUP BY promo_id),
+     impression_counts AS (SELECT promo_id, count(*) AS count
+                           FROM promo_views
+                           WHERE promo_id IN (SELECT id FROM company_promos)
+                             AND kind = 'IMPRESSION'
+                           GROUP BY promo_id),
+     view_counts AS (SELECT promo_id, count(*) AS count
+                     FROM promo_views
+                     WHERE promo_id IN (SELECT id FROM company_promos)
+                       AND kind = 'VIEW'
+                     GROUP BY promo_id),
+     viewer_counts AS (SELECT promo_id, count(DISTINCT user_id) AS count
+                       FROM (SELECT promo_id, user_id
+                             FROM promo_views
+                             WHERE kind = 'VIEW'
+                             UNION ALL
+                             SELECT promo_id, user_id
+                             FROM activations
+                             UNION ALL
+                             SELECT promo_id, user_id
+                             FROM likes
+                             UNION ALL
+                             SELECT promo_id, author_id
+                             FROM comments) AS interactions
+                       WHERE promo_id IN (SELECT id FROM company_promos)
+                       GROUP BY promo_id)
+SELECT company_promos.id                      AS "promo_id!",
+       company_promos.description             AS "description!",
+       company_promos.active                  AS "active!",
+       coalesce(activation_counts.count, 0)   AS "activations_count!",
+       coalesce(like_counts.count, 0)         AS "likes_count!",
+       coalesce(comment_counts.count, 0)      AS "comments_count!",
+       coalesce(impression_counts.count, 0)   AS "impressions_count!",
+       coalesce(view_counts.count, 0)         AS "views_count!",
+       coalesce(viewer_counts.count, 0)       AS "unique_viewers!"
+FROM company_promos
+         LEFT JOIN activation_counts ON activation_counts.promo_id = company_promos.id
+         LEFT JOIN like_counts ON like_counts.promo_id = company_promos.id
+         LEFT JOIN comment_counts ON comment_counts.promo_id = company_promos.id
+         LEFT JOIN impression_counts ON impression_counts.promo_id = company_promos.id
+         LEFT JOIN view_counts ON view_counts.promo_id = company_promos.id
+         LEFT JOIN viewer_counts ON viewer_counts.promo_id = company_promos.id
+ORDER BY company_promos.id
\ No newline at end of file
@@ -0,0 +1,7 @@
+SELECT lower((users.other).country) as country, count(*) as activations_count
+FROM activations
+         LEFT JOIN users ON users.id = user_id
+WHERE promo_id IN (SELECT id FROM promos WHERE company_id = $1)
+GROUP BY country
+ORDER BY activations_count DESC, country
+LIMIT $2
\ No newline at end of file
@@ -0,0 +1,14 @@
+SELECT count(DISTINCT user_id) AS "unique_viewers!"
+FROM (SELECT promo_id, user_id
+      FROM promo_views
+      WHERE kind = 'VIEW'
+      UNION ALL
+      SELECT promo_id, user_id
+      FROM activations
+      UNION ALL
+      SELECT promo_id, user_id
+      FROM likes
+      UNION ALL
+      SELECT promo_id, author_id
+      FROM comments) AS interactions
+WHERE promo_id IN (SELECT id FROM promos WHERE company_id = $1)
\ No newline at end of file
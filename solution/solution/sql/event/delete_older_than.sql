@@ -0,0 +1,3 @@
+DELETE
+FROM events
+WHERE created_at < $1
\ No newline at end of file
@@ -0,0 +1,6 @@
+SELECT id, transaction_id, event, company_id, promo_id, payload, created_at
+FROM events
+WHERE (transaction_id, id) > ($1, $2)
+  AND transaction_id < pg_snapshot_xmin(pg_current_snapshot())::text::bigint
+ORDER BY transaction_id, id
+LIMIT $3
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint AS "transaction_id!"
\ No newline at end of file
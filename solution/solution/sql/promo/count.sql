@@ -1,3 +0,0 @@
-SELECT count(*)
-FROM promos
-WHERE company_id = $1
\ No newline at end of file
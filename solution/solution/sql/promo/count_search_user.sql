@@ -0,0 +1,7 @@
+SELECT count(*)
+FROM promos
+WHERE search_vector @@ websearch_to_tsquery('simple', $2)
+  AND target_matches(target, (SELECT other FROM users WHERE id = $1), (SELECT user_interests($1)))
+  AND ($3::text IS NULL OR lower($3) = ANY (lower((target).categories::text)::text[]))
+  AND ($4::bool IS NULL OR promos.active = $4)
+  AND promos.status = 'PUBLISHED'
\ No newline at end of file
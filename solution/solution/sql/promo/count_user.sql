@@ -1,4 +0,0 @@
-SELECT count(*)
-FROM promos
-WHERE (target).country IS NULL
-   OR lower((target).country) = lower($1)
\ No newline at end of file
@@ -9,11 +9,16 @@ SELECT promos.id,
        active_until,
        mode           AS "mode: DBPromoMode",
        promo_common,
-       promo_unique,
+       promo_code_count(promos.id) AS "unique_count!",
+       promo_code_count(promos.id, 'AVAILABLE') AS "unique_available!",
        like_count,
        used_count,
        comment_count,
-       active
+       active,
+       per_user_limit,
+       cooldown,
+       max_per_day,
+       status         AS "status: DBPromoStatus"
 FROM promos
          LEFT JOIN companies ON companies.id = company_id
 WHERE promos.id = $1
\ No newline at end of file
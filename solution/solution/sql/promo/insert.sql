@@ -1,7 +1,8 @@
 WITH inserted_promo AS (
     INSERT INTO promos (id, company_id, description, image_url, target, max_count, active_from, active_until, mode,
-                        promo_common, promo_unique, like_count, used_count, comment_count, active)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                        promo_common, like_count, used_count, comment_count, active, per_user_limit, cooldown,
+                        max_per_day, status)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
         RETURNING *)
 SELECT inserted_promo.id,
        company_id,
@@ -14,10 +15,15 @@ SELECT inserted_promo.id,
        active_until,
        mode           AS "mode: DBPromoMode",
        promo_common,
-       promo_unique,
+       promo_code_count(inserted_promo.id) AS "unique_count!",
+       promo_code_count(inserted_promo.id, 'AVAILABLE') AS "unique_available!",
        like_count,
        used_count,
        comment_count,
-       active
+       active,
+       per_user_limit,
+       cooldown,
+       max_per_day,
+       status         AS "status: DBPromoStatus"
 FROM inserted_promo
          LEFT JOIN companies ON companies.id = company_id
\ No newline at end of file
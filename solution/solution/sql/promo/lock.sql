@@ -0,0 +1,4 @@
+SELECT id
+FROM promos
+WHERE id = $1
+    FOR UPDATE
\ No newline at end of file
@@ -0,0 +1,5 @@
+SELECT target_matches(promos.target, users.other, user_interests(users.id)) AS "matches!"
+FROM promos,
+     users
+WHERE promos.id = $1
+  AND users.id = $2
\ No newline at end of file
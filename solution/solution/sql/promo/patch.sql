@@ -5,7 +5,10 @@ WITH updated_promo AS (
             target = coalesce($4, target),
             max_count = coalesce($5, max_count),
             active_from = coalesce($6, active_from),
-            active_until = coalesce($7, active_until)
+            active_until = coalesce($7, active_until),
+            per_user_limit = coalesce($8, per_user_limit),
+            cooldown = coalesce($9, cooldown),
+            max_per_day = coalesce($10, max_per_day)
         WHERE id = $1
         RETURNING *)
 SELECT updated_promo.id,
@@ -19,10 +22,15 @@ SELECT updated_promo.id,
        active_until,
        mode           AS "mode: DBPromoMode",
        promo_common,
-       promo_unique,
+       promo_code_count(updated_promo.id) AS "unique_count!",
+       promo_code_count(updated_promo.id, 'AVAILABLE') AS "unique_available!",
        like_count,
        used_count,
        comment_count,
-       active
+       active,
+       per_user_limit,
+       cooldown,
+       max_per_day,
+       status         AS "status: DBPromoStatus"
 FROM updated_promo
          LEFT JOIN companies ON companies.id = company_id
\ No newline at end of file
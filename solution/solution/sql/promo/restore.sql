@@ -0,0 +1,36 @@
+WITH updated_promo AS (
+    UPDATE promos
+        SET description = $2,
+            image_url = $3,
+            target = $4,
+            max_count = $5,
+            active_from = $6,
+            active_until = $7,
+            per_user_limit = $8,
+            cooldown = $9,
+            max_per_day = $10
+        WHERE id = $1
+        RETURNING *)
+SELECT updated_promo.id,
+       company_id,
+       companies.name AS company_name,
+       description,
+       image_url,
+       target         AS "target: DBTarget",
+       max_count,
+       active_from,
+       active_until,
+       mode           AS "mode: DBPromoMode",
+       promo_common,
+       promo_code_count(updated_promo.id) AS "unique_count!",
+       promo_code_count(updated_promo.id, 'AVAILABLE') AS "unique_available!",
+       like_count,
+       used_count,
+       comment_count,
+       active,
+       per_user_limit,
+       cooldown,
+       max_per_day,
+       status         AS "status: DBPromoStatus"
+FROM updated_promo
+         LEFT JOIN companies ON companies.id = company_id
\ No newline at end of file
@@ -0,0 +1,31 @@
+SELECT promos.id,
+       company_id,
+       companies.name AS company_name,
+       description,
+       image_url,
+       target         AS "target: DBTarget",
+       max_count,
+       active_from,
+       active_until,
+       mode           AS "mode: DBPromoMode",
+       promo_common,
+       promo_code_count(promos.id) AS "unique_count!",
+       promo_code_count(promos.id, 'AVAILABLE') AS "unique_available!",
+       like_count,
+       used_count,
+       comment_count,
+       active,
+       per_user_limit,
+       cooldown,
+       max_per_day,
+       status         AS "status: DBPromoStatus"
+FROM promos
+         LEFT JOIN companies ON companies.id = company_id,
+     websearch_to_tsquery('simple', $2) AS query
+WHERE search_vector @@ query
+  AND target_matches(target, (SELECT other FROM users WHERE id = $1), (SELECT user_interests($1)))
+  AND ($3::text IS NULL OR lower($3) = ANY (lower((target).categories::text)::text[]))
+  AND ($4::bool IS NULL OR promos.active = $4)
+  AND promos.status = 'PUBLISHED'
+ORDER BY ts_rank(search_vector, query) DESC, id DESC
+LIMIT $5 OFFSET $6
\ No newline at end of file
@@ -0,0 +1,4 @@
+UPDATE promos
+SET status = $3
+WHERE id = $1
+  AND status = $2
\ No newline at end of file
@@ -0,0 +1,7 @@
+SELECT activations.user_id, activations.promo_id, activations.promo, activations.date, activations.redeemed_at
+FROM activations
+         JOIN promos ON promos.id = activations.promo_id
+WHERE promos.company_id = $1
+  AND promos.mode = 'UNIQUE'
+  AND activations.promo = $2
+  AND ($3::uuid IS NULL OR activations.promo_id = $3)
\ No newline at end of file
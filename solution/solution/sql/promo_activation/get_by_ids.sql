@@ -1,7 +1,8 @@
 SELECT user_id,
        promo_id,
        promo,
-       date
+       date,
+       redeemed_at
 FROM activations
 WHERE user_id = $1
   AND promo_id = $2
@@ -1,4 +1,5 @@
 WITH activations AS (SELECT promo_id,
+                            date,
                             company_id,
                             description,
                             image_url,
@@ -8,15 +9,19 @@ WITH activations AS (SELECT promo_id,
                             active_until,
                             mode,
                             promo_common,
-                            promo_unique,
                             like_count,
                             used_count,
                             comment_count,
-                            active
+                            active,
+                            per_user_limit,
+                            cooldown,
+                            max_per_day,
+                            status
                      FROM activations
                               LEFT JOIN promos ON promos.id = activations.promo_id
                      WHERE user_id = $1
-                     ORDER BY date DESC
+                       AND ($4::timestamptz IS NULL OR (date, promo_id) < ($4, $5))
+                     ORDER BY date DESC, promo_id DESC
                      LIMIT $2 OFFSET $3)
 SELECT promo_id as id,
        company_id,
@@ -29,11 +34,17 @@ SELECT promo_id as id,
        active_until,
        mode           AS "mode: DBPromoMode",
        promo_common,
-       promo_unique,
+       promo_code_count(promo_id) AS "unique_count!",
+       promo_code_count(promo_id, 'AVAILABLE') AS "unique_available!",
        like_count,
        used_count,
        comment_count,
-       active
+       active,
+       per_user_limit,
+       cooldown,
+       max_per_day,
+       status         AS "status: DBPromoStatus",
+       date           AS activated_at
 FROM activations
          LEFT JOIN companies ON companies.id = company_id
-ORDER BY id DESC
+ORDER BY activated_at DESC, id DESC
@@ -0,0 +1,2 @@
+SELECT 1 AS "locked!"
+FROM pg_advisory_xact_lock(hashtextextended($1::uuid::text || $2::uuid::text, 0))
\ No newline at end of file
@@ -0,0 +1,6 @@
+UPDATE activations
+SET redeemed_at = $3
+WHERE promo_id = $1
+  AND promo = $2
+  AND redeemed_at IS NULL
+RETURNING user_id, promo_id, promo, date, redeemed_at
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT count(*) AS "issued_count!", count(redeemed_at) AS "redeemed_count!"
+FROM activations
+WHERE promo_id = $1
\ No newline at end of file
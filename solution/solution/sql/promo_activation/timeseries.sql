@@ -0,0 +1,20 @@
+SELECT date_trunc($2, date, 'UTC')                 AS "bucket!",
+       CASE WHEN $5 THEN coalesce(lower((users.other).country), 'unknown') END AS country,
+       CASE
+           WHEN NOT $6 THEN NULL
+           WHEN (users.other).age IS NULL THEN 'unknown'
+           WHEN (users.other).age < 18 THEN '<18'
+           WHEN (users.other).age < 25 THEN '18-24'
+           WHEN (users.other).age < 35 THEN '25-34'
+           WHEN (users.other).age < 45 THEN '35-44'
+           WHEN (users.other).age < 55 THEN '45-54'
+           ELSE '55+'
+           END                                     AS age_band,
+       count(*)                                    AS "activations_count!"
+FROM activations
+         LEFT JOIN users ON users.id = user_id
+WHERE promo_id = $1
+  AND date >= $3
+  AND date < $4
+GROUP BY 1, 2, 3
+ORDER BY 1, 2, 3
\ No newline at end of file
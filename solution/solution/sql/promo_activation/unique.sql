@@ -1,9 +1,22 @@
-WITH unique_promo AS (
-    UPDATE promos
-        SET used_count = used_count + 1
-        WHERE id = $2
-        RETURNING promos.promo_unique[used_count] AS promo
-)
+WITH code AS (SELECT promo_id, code
+              FROM promo_codes
+              WHERE promo_id = $2
+                AND status = 'AVAILABLE'
+              ORDER BY position
+              LIMIT 1 FOR UPDATE SKIP LOCKED),
+     issued AS (
+         UPDATE promo_codes
+             SET status = 'ISSUED', reserved_by = $1, issued_at = $3
+             FROM code
+             WHERE promo_codes.promo_id = code.promo_id
+               AND promo_codes.code = code.code
+             RETURNING promo_codes.code),
+     counted AS (
+         UPDATE promos
+             SET used_count = used_count + 1
+             WHERE id = $2
+               AND EXISTS (SELECT 1 FROM issued))
 INSERT INTO activations (user_id, promo_id, promo, date)
-VALUES ($1, $2, (SELECT promo FROM unique_promo), $3)
+SELECT $1, $2, issued.code, $3
+FROM issued
 RETURNING *
\ No newline at end of file
@@ -0,0 +1,12 @@
+SELECT (SELECT count(*)
+        FROM activations
+        WHERE user_id = $1
+          AND promo_id = $2)  AS "user_activations!",
+       (SELECT max(date)
+        FROM activations
+        WHERE user_id = $1
+          AND promo_id = $2)  AS last_activation,
+       (SELECT count(*)
+        FROM activations
+        WHERE promo_id = $2
+          AND date >= date_trunc('day', $3, 'UTC')) AS "today_activations!"
\ No newline at end of file
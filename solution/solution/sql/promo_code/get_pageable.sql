@@ -0,0 +1,5 @@
+SELECT code
+FROM promo_codes
+WHERE promo_id = $1
+ORDER BY position
+LIMIT $2 OFFSET $3
\ No newline at end of file
@@ -0,0 +1,7 @@
+INSERT
+INTO promo_codes (promo_id, code, position)
+SELECT $1,
+       codes.code,
+       (SELECT coalesce(max(position), 0) FROM promo_codes WHERE promo_id = $1) + codes.position
+FROM unnest($2::text[]) WITH ORDINALITY AS codes(code, position)
+ON CONFLICT DO NOTHING
\ No newline at end of file
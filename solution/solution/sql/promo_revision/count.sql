@@ -0,0 +1,3 @@
+SELECT count(*)
+FROM promo_revisions
+WHERE promo_id = $1
\ No newline at end of file
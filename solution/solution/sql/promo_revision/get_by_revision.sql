@@ -0,0 +1,12 @@
+SELECT id,
+       promo_id,
+       revision,
+       author AS "author: DBRevisionAuthor",
+       member_id,
+       restored_from,
+       changes,
+       snapshot,
+       created_at
+FROM promo_revisions
+WHERE promo_id = $1
+  AND revision = $2
\ No newline at end of file
@@ -0,0 +1,14 @@
+SELECT id,
+       promo_id,
+       revision,
+       author AS "author: DBRevisionAuthor",
+       member_id,
+       restored_from,
+       changes,
+       snapshot,
+       created_at
+FROM promo_revisions
+WHERE promo_id = $1
+  AND ($4::uuid IS NULL OR id < $4)
+ORDER BY id DESC
+LIMIT $2 OFFSET $3
\ No newline at end of file
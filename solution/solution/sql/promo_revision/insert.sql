@@ -0,0 +1,14 @@
+INSERT
+INTO promo_revisions (id, promo_id, revision, author, member_id, restored_from, changes, snapshot, created_at)
+SELECT $1, $2, coalesce(max(revision), 0) + 1, $3, $4, $5, $6, $7, $8
+FROM promo_revisions
+WHERE promo_id = $2
+RETURNING id,
+          promo_id,
+          revision,
+          author AS "author: DBRevisionAuthor",
+          member_id,
+          restored_from,
+          changes,
+          snapshot,
+          created_at
\ No newline at end of file
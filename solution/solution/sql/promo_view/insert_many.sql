@@ -0,0 +1,6 @@
+INSERT INTO promo_views (promo_id, user_id, kind, day)
+SELECT views.promo_id, views.user_id, views.kind::promo_view_kind, views.day
+FROM unnest($1::uuid[], $2::uuid[], $3::text[], $4::date[]) AS views (promo_id, user_id, kind, day)
+WHERE EXISTS (SELECT 1 FROM promos WHERE promos.id = views.promo_id)
+  AND EXISTS (SELECT 1 FROM users WHERE users.id = views.user_id)
+ON CONFLICT DO NOTHING
\ No newline at end of file
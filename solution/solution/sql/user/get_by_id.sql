@@ -1,3 +1,3 @@
-SELECT id, name, surname, email, avatar_url, other AS "other: DBUserTargetSettings", password_hash
+SELECT id, name, surname, email, avatar_url, other AS "other: DBUserTargetSettings", password_hash, interests
 FROM users
 WHERE id = $1
\ No newline at end of file
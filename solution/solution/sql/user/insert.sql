@@ -1,3 +1,3 @@
-INSERT INTO users (id, name, surname, email, avatar_url, other, password_hash)
-VALUES ($1, $2, $3, $4, $5, $6, $7)
-RETURNING id, name, surname, email, avatar_url, other AS "other: DBUserTargetSettings", password_hash
\ No newline at end of file
+INSERT INTO users (id, name, surname, email, avatar_url, other, password_hash, interests)
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+RETURNING id, name, surname, email, avatar_url, other AS "other: DBUserTargetSettings", password_hash, interests
\ No newline at end of file
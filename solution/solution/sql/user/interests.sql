@@ -0,0 +1 @@
+SELECT user_interests($1) AS "interests!"
\ No newline at end of file
@@ -2,6 +2,7 @@ UPDATE users
 SET name          = COALESCE($2, name),
     surname       = COALESCE($3, surname),
     avatar_url    = COALESCE($4, avatar_url),
-    password_hash = COALESCE($5, password_hash)
+    password_hash = COALESCE($5, password_hash),
+    interests     = COALESCE($6, interests)
 WHERE id = $1
-RETURNING id, name, surname, email, avatar_url, other AS "other: DBUserTargetSettings", password_hash
\ No newline at end of file
+RETURNING id, name, surname, email, avatar_url, other AS "other: DBUserTargetSettings", password_hash, interests
\ No newline at end of file
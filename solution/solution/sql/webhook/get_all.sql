@@ -0,0 +1,4 @@
+SELECT id, company_id, url, secret, events, active, created_at
+FROM webhooks
+WHERE company_id = $1
+ORDER BY created_at DESC
\ No newline at end of file
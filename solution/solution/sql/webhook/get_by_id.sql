@@ -0,0 +1,3 @@
+SELECT id, company_id, url, secret, events, active, created_at
+FROM webhooks
+WHERE id = $1
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO webhooks (id, company_id, url, secret, events, active, created_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+RETURNING id, company_id, url, secret, events, active, created_at
\ No newline at end of file
@@ -0,0 +1,6 @@
+UPDATE webhooks
+SET url    = coalesce($2, url),
+    events = coalesce($3, events),
+    active = coalesce($4, active)
+WHERE id = $1
+RETURNING id, company_id, url, secret, events, active, created_at
\ No newline at end of file
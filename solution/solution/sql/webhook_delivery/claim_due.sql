@@ -0,0 +1,9 @@
+UPDATE webhook_deliveries
+SET next_attempt_at = now() + make_interval(secs => $2)
+WHERE id IN (SELECT id
+             FROM webhook_deliveries
+             WHERE status = 'PENDING'
+               AND next_attempt_at <= now()
+             ORDER BY next_attempt_at
+             LIMIT $1 FOR UPDATE SKIP LOCKED)
+RETURNING id, webhook_id, event, payload, status AS "status: DBWebhookDeliveryStatus", attempts, response_status, last_error, created_at, next_attempt_at, last_attempt_at
\ No newline at end of file
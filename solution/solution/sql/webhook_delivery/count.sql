@@ -0,0 +1,3 @@
+SELECT count(*)
+FROM webhook_deliveries
+WHERE webhook_id = $1
\ No newline at end of file
@@ -0,0 +1,6 @@
+SELECT id, webhook_id, event, payload, status AS "status: DBWebhookDeliveryStatus", attempts, response_status, last_error, created_at, next_attempt_at, last_attempt_at
+FROM webhook_deliveries
+WHERE webhook_id = $1
+  AND ($4::timestamptz IS NULL OR (created_at, id) < ($4, $5))
+ORDER BY created_at DESC, id DESC
+LIMIT $2 OFFSET $3
\ No newline at end of file
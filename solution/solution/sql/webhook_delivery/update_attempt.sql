@@ -0,0 +1,8 @@
+UPDATE webhook_deliveries
+SET status          = $2,
+    attempts        = $3,
+    response_status = $4,
+    last_error      = $5,
+    next_attempt_at = $6,
+    last_attempt_at = $7
+WHERE id = $1
\ No newline at end of file
@@ -3,9 +3,13 @@ use thiserror::Error;
 
 use crate::models::ApiError;
 
+mod signing;
 mod validate;
 
-pub use validate::{auth_middleware, auth_middleware_cmp, auth_middleware_usr};
+pub use signing::{SigningKey, SigningKeys};
+pub use validate::{
+    auth_middleware, auth_middleware_cmp, auth_middleware_cmp_token, auth_middleware_usr,
+};
 
 #[derive(Error, Debug)]
 pub enum AuthenticationError {
@@ -27,6 +31,12 @@ pub enum AuthenticationError {
     #[error("Authentication method was not valid")]
     InvalidAuthMethod,
 
+    #[error("Token has expired")]
+    ExpiredToken,
+
+    #[error("Refresh token has already been used, the session was revoked")]
+    RefreshTokenReused,
+
     #[error("Incorrect token type")]
     IcorrectTokenType,
 
@@ -46,6 +56,8 @@ impl ResponseError for AuthenticationError {
             Self::NoAuthorizationHeader => StatusCode::UNAUTHORIZED,
             Self::InvalidCredentials => StatusCode::UNAUTHORIZED,
             Self::InvalidAuthMethod => StatusCode::UNAUTHORIZED,
+            Self::ExpiredToken => StatusCode::UNAUTHORIZED,
+            Self::RefreshTokenReused => StatusCode::UNAUTHORIZED,
             Self::IcorrectTokenType => StatusCode::FORBIDDEN,
             Self::DuplicateCompany => StatusCode::CONFLICT,
             Self::DuplicateUser => StatusCode::CONFLICT,
@@ -69,6 +81,8 @@ impl AuthenticationError {
             Self::NoAuthorizationHeader => "missing_authorization_header",
             Self::InvalidCredentials => "invalid_credentials",
             Self::InvalidAuthMethod => "invalid_auth_method",
+            Self::ExpiredToken => "expired_token",
+            Self::RefreshTokenReused => "refresh_token_reused",
             Self::IcorrectTokenType => "incorrect_token_type",
             Self::DuplicateCompany => "duplicate_company",
             Self::DuplicateUser => "duplicate_user",
@@ -0,0 +1,117 @@
+use std::{env, sync::OnceLock};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Shorter secrets are too easy to brute-force from a single issued token.
+const MIN_SECRET_LENGTH: usize = 32;
+
+pub struct SigningKey {
+    pub id: String,
+    secret: Vec<u8>,
+}
+
+impl SigningKey {
+    pub fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts any key size");
+        mac.update(payload);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    pub fn verify(&self, payload: &[u8], signature: &[u8]) -> bool {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC accepts any key size");
+        mac.update(payload);
+        mac.verify_slice(signature).is_ok()
+    }
+}
+
+pub struct SigningKeys {
+    keys: Vec<SigningKey>,
+}
+
+impl SigningKeys {
+    /// Panics if `TOKEN_SIGNING_KEYS` is missing or invalid, there is no
+    /// safe default to sign tokens with.
+    pub fn get() -> &'static Self {
+        static KEYS: OnceLock<SigningKeys> = OnceLock::new();
+
+        KEYS.get_or_init(|| {
+            let value = env::var("TOKEN_SIGNING_KEYS")
+                .expect("Variable `TOKEN_SIGNING_KEYS` is missing in env!");
+
+            Self::parse(&value).unwrap_or_else(|err| panic!("`TOKEN_SIGNING_KEYS` {err}"))
+        })
+    }
+
+    // `TOKEN_SIGNING_KEYS` is a comma-separated list of `key_id:secret` pairs.
+    // The first key signs new tokens, the others are only accepted when
+    // verifying, so a key can be rotated out once its tokens have expired.
+    fn parse(value: &str) -> Result<Self, String> {
+        let keys = value
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (id, secret) = pair
+                    .split_once(':')
+                    .ok_or_else(|| "items must look like `key_id:secret`".to_string())?;
+
+                if id.is_empty() || id.contains('.') {
+                    return Err(format!("contains an invalid key id `{id}`"));
+                }
+                if secret.len() < MIN_SECRET_LENGTH {
+                    return Err(format!(
+                        "key `{id}` must have a secret of at least {MIN_SECRET_LENGTH} bytes"
+                    ));
+                }
+
+                Ok(SigningKey {
+                    id: id.to_string(),
+                    secret: secret.as_bytes().to_vec(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if keys.is_empty() {
+            return Err("must contain at least one key".to_string());
+        }
+
+        Ok(Self { keys })
+    }
+
+    pub fn current(&self) -> &SigningKey {
+        &self.keys[0]
+    }
+
+    pub fn find(&self, id: &str) -> Option<&SigningKey> {
+        self.keys.iter().find(|key| key.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn parses_rotated_keys() {
+        let keys = SigningKeys::parse(&format!("new:{SECRET}, old:{SECRET}")).unwrap();
+
+        assert_eq!(keys.current().id, "new");
+        assert!(keys.find("old").is_some());
+        assert!(keys.find("missing").is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        assert!(SigningKeys::parse("").is_err());
+        assert!(SigningKeys::parse(" , ").is_err());
+        assert!(SigningKeys::parse(SECRET).is_err());
+        assert!(SigningKeys::parse(&format!(":{SECRET}")).is_err());
+        assert!(SigningKeys::parse(&format!("a.b:{SECRET}")).is_err());
+        assert!(SigningKeys::parse("default:change-me-in-production").is_err());
+    }
+}
@@ -6,21 +6,65 @@ use actix_web::{
     web::Data,
     Error, HttpMessage,
 };
+use sqlx::PgPool;
 
 use crate::{
     database::redis::RedisPool,
-    models::{Token, TokenType},
+    models::{ApiKeyToken, CompanyActor, Token, TokenType},
     routes::ApiError,
 };
 
 use super::AuthenticationError;
 
+const API_KEY_HEADER: &str = "X-API-Key";
+
 pub async fn auth_middleware_cmp(
     cache: Data<RedisPool>,
+    pool: Data<PgPool>,
     req: ServiceRequest,
     next: Next<impl MessageBody>,
 ) -> Result<ServiceResponse<impl MessageBody>, Error> {
-    auth_middleware(cache, req, next, TokenType::Company).await
+    if let Some(api_key) = extract_api_key_from_header(&req)? {
+        let actor = CompanyActor::resolve_api_key(&api_key, &**pool).await?;
+
+        req.extensions_mut().insert(actor);
+    } else {
+        let (token, actor) = resolve_company_token(cache, pool, &req).await?;
+
+        req.extensions_mut().insert(token);
+        req.extensions_mut().insert(actor);
+    }
+
+    next.call(req).await
+}
+
+pub async fn auth_middleware_cmp_token(
+    cache: Data<RedisPool>,
+    pool: Data<PgPool>,
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let (token, actor) = resolve_company_token(cache, pool, &req).await?;
+
+    req.extensions_mut().insert(token);
+    req.extensions_mut().insert(actor);
+
+    next.call(req).await
+}
+
+async fn resolve_company_token(
+    cache: Data<RedisPool>,
+    pool: Data<PgPool>,
+    req: &ServiceRequest,
+) -> Result<(Token, CompanyActor), Error> {
+    let token = extract_token_from_authorization_header(req)?
+        .validate(&mut cache.connect().await.map_err(ApiError::Database)?)
+        .await?;
+    token.touch_session(&cache);
+
+    let actor = CompanyActor::resolve(&token, &**pool).await?;
+
+    Ok((token, actor))
 }
 
 pub async fn auth_middleware_usr(
@@ -37,11 +81,10 @@ pub async fn auth_middleware(
     next: Next<impl MessageBody>,
     allowed_tokens: TokenType,
 ) -> Result<ServiceResponse<impl MessageBody>, Error> {
-    let mut cache = cache.connect().await.map_err(ApiError::Database)?;
-
     let token = extract_token_from_authorization_header(&req)?
-        .validate(&mut cache)
+        .validate(&mut cache.connect().await.map_err(ApiError::Database)?)
         .await?;
+    token.touch_session(&cache);
 
     if token.token_type != allowed_tokens {
         return Err(AuthenticationError::IcorrectTokenType)?;
@@ -62,8 +105,27 @@ pub fn extract_token_from_authorization_header(
         .to_str()
         .map_err(|_| AuthenticationError::InvalidCredentials)?;
     if let Some(token) = token_val.strip_prefix("Bearer ") {
-        Ok(Token::from_str(token).map_err(|_| AuthenticationError::InvalidCredentials)?)
+        token
+            .parse()
+            .map_err(|_| AuthenticationError::InvalidCredentials)
     } else {
         Err(AuthenticationError::InvalidAuthMethod)
     }
 }
+
+pub fn extract_api_key_from_header(
+    req: &ServiceRequest,
+) -> Result<Option<ApiKeyToken>, AuthenticationError> {
+    let api_key_val = if let Some(api_key_val) = req.headers().get(API_KEY_HEADER) {
+        api_key_val
+            .to_str()
+            .map_err(|_| AuthenticationError::InvalidCredentials)?
+    } else {
+        return Ok(None);
+    };
+
+    api_key_val
+        .parse()
+        .map(Some)
+        .map_err(|_| AuthenticationError::InvalidCredentials)
+}
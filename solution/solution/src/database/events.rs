@@ -0,0 +1,134 @@
+use std::{collections::VecDeque, time::Duration};
+
+use futures::{stream, Stream};
+use log::warn;
+use sqlx::{postgres::PgListener, PgPool};
+
+use crate::models::Event;
+
+use super::models::{DBEvent, DatabaseError};
+
+const EVENTS_CHANNEL: &str = "events";
+const EVENTS_BATCH_SIZE: i64 = 100;
+const EVENTS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where a consumer stopped reading, events are ordered by the transaction
+/// that wrote them and then by id.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EventPosition {
+    pub transaction_id: i64,
+    pub id: i64,
+}
+
+pub struct EventStream {
+    pool: PgPool,
+    listener: Option<PgListener>,
+    position: EventPosition,
+    buffer: VecDeque<Event>,
+}
+
+impl EventStream {
+    /// Starts after `after`, or with the events of transactions that are
+    /// still running if it's not given.
+    pub async fn subscribe(
+        pool: PgPool,
+        after: Option<EventPosition>,
+    ) -> Result<Self, DatabaseError> {
+        let listener = match PgListener::connect_with(&pool).await {
+            Ok(mut listener) => match listener.listen(EVENTS_CHANNEL).await {
+                Ok(()) => Some(listener),
+                Err(e) => {
+                    warn!(
+                        "Listening for events failed, falling back to polling: {:?}",
+                        e
+                    );
+                    None
+                }
+            },
+            Err(e) => {
+                warn!(
+                    "Connecting event listener failed, falling back to polling: {:?}",
+                    e
+                );
+                None
+            }
+        };
+
+        let position = match after {
+            Some(position) => position,
+            None => EventPosition {
+                transaction_id: DBEvent::head(&pool).await?,
+                id: 0,
+            },
+        };
+
+        Ok(Self {
+            pool,
+            listener,
+            position,
+            buffer: VecDeque::new(),
+        })
+    }
+
+    pub fn position(&self) -> EventPosition {
+        self.position
+    }
+
+    pub async fn next(&mut self) -> Result<Event, DatabaseError> {
+        loop {
+            if let Some(event) = self.buffer.pop_front() {
+                self.position = EventPosition {
+                    transaction_id: event.transaction_id,
+                    id: event.id,
+                };
+                return Ok(event);
+            }
+
+            let events = DBEvent::get_after(
+                self.position.transaction_id,
+                self.position.id,
+                EVENTS_BATCH_SIZE,
+                &self.pool,
+            )
+            .await?;
+            if !events.is_empty() {
+                self.buffer
+                    .extend(events.into_iter().map(DBEvent::into_model));
+                continue;
+            }
+
+            self.wait().await;
+        }
+    }
+
+    /// Waits for the next event and returns it along with every event that
+    /// is already fetched.
+    pub async fn next_batch(&mut self) -> Result<Vec<Event>, DatabaseError> {
+        let mut events = vec![self.next().await?];
+        while !self.buffer.is_empty() {
+            events.push(self.next().await?);
+        }
+        Ok(events)
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = Result<Event, DatabaseError>> {
+        stream::unfold(self, |mut events| async move {
+            let event = events.next().await;
+            Some((event, events))
+        })
+    }
+
+    async fn wait(&mut self) {
+        let listener = if let Some(listener) = &mut self.listener {
+            listener
+        } else {
+            actix_rt::time::sleep(EVENTS_POLL_INTERVAL).await;
+            return;
+        };
+
+        if let Ok(Err(e)) = actix_rt::time::timeout(EVENTS_POLL_INTERVAL, listener.recv()).await {
+            warn!("Event listener failed, falling back to polling: {:?}", e);
+            self.listener = None;
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod events;
 pub mod models;
 mod postgres;
 pub mod redis;
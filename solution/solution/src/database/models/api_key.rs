@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, query_file_as, Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::ApiKey;
+
+use super::DatabaseError;
+
+#[derive(Debug)]
+pub struct DBApiKey {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub name: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl DBApiKey {
+    pub async fn insert(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/api_key/insert.sql",
+            self.id,
+            self.company_id,
+            self.name,
+            self.key_hash,
+            &self.scopes,
+            self.created_at,
+            self.expires_at
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn get_by_id<'a, E>(id: Uuid, executor: E) -> Result<Option<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(Self, "sql/api_key/get_by_id.sql", id)
+            .fetch_optional(executor)
+            .await?)
+    }
+
+    pub async fn get_all<'a, E>(company_id: Uuid, executor: E) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(Self, "sql/api_key/get_all.sql", company_id)
+            .fetch_all(executor)
+            .await?)
+    }
+
+    pub async fn delete(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), DatabaseError> {
+        query_file!("sql/api_key/delete.sql", self.id)
+            .execute(&mut **transaction)
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+
+    pub fn into_model(self) -> ApiKey {
+        ApiKey::from(self)
+    }
+}
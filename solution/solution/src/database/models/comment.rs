@@ -3,7 +3,7 @@ use sqlx::{query_file, query_file_as, Executor, Postgres, Transaction};
 use std::i64;
 use uuid::Uuid;
 
-use crate::models::Comment;
+use crate::models::{Comment, PageRequest};
 
 use super::DatabaseError;
 
@@ -39,29 +39,34 @@ impl DBComment {
 
     pub async fn get_pageable<'a, E>(
         promo_id: Uuid,
-        limit: i64,
-        offset: i64,
+        page: &PageRequest,
         executor: E,
-    ) -> Result<(Vec<DBComment>, i64), DatabaseError>
+    ) -> Result<(Vec<DBComment>, Option<i64>), DatabaseError>
     where
         E: Executor<'a, Database = Postgres> + Copy,
     {
-        Ok((
-            query_file_as!(
-                Self,
-                "sql/comment/get_pageable.sql",
-                promo_id,
-                limit,
-                offset
-            )
-            .fetch_all(executor)
-            .await?,
-            query_file!("sql/comment/count.sql", promo_id)
-                .fetch_one(executor)
-                .await?
-                .count
-                .unwrap(),
-        ))
+        let comments = query_file_as!(
+            Self,
+            "sql/comment/get_pageable.sql",
+            promo_id,
+            page.limit,
+            page.offset,
+            page.cursor_date(),
+            page.cursor_id()
+        )
+        .fetch_all(executor)
+        .await?;
+
+        if !page.with_total {
+            return Ok((comments, None));
+        }
+
+        let count = query_file!("sql/comment/count.sql", promo_id)
+            .fetch_one(executor)
+            .await?
+            .count;
+
+        Ok((comments, count))
     }
 
     pub async fn get_by_id<'a, E>(
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::Type, query_file, query_file_as, Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::CompanyMember;
+
+use super::DatabaseError;
+
+#[derive(Type, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "company_role")]
+pub enum DBCompanyRole {
+    OWNER,
+    MANAGER,
+    ANALYST,
+}
+
+#[derive(Debug)]
+pub struct DBCompanyMember {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub role: DBCompanyRole,
+    pub password_hash: Option<String>,
+    pub invite_hash: Option<String>,
+    pub invited_at: DateTime<Utc>,
+    pub joined_at: Option<DateTime<Utc>>,
+}
+
+impl DBCompanyMember {
+    pub async fn insert(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/company_member/insert.sql",
+            self.id,
+            self.company_id,
+            self.email,
+            self.name,
+            self.role as DBCompanyRole,
+            self.password_hash,
+            self.invite_hash,
+            self.invited_at,
+            self.joined_at
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn get_by_id<'a, E>(id: Uuid, executor: E) -> Result<Option<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(Self, "sql/company_member/get_by_id.sql", id)
+            .fetch_optional(executor)
+            .await?)
+    }
+
+    pub async fn get_by_email<'a, E>(
+        email: &str,
+        executor: E,
+    ) -> Result<Option<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(
+            query_file_as!(Self, "sql/company_member/get_by_email.sql", email)
+                .fetch_optional(executor)
+                .await?,
+        )
+    }
+
+    pub async fn get_all<'a, E>(company_id: Uuid, executor: E) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(
+            query_file_as!(Self, "sql/company_member/get_all.sql", company_id)
+                .fetch_all(executor)
+                .await?,
+        )
+    }
+
+    pub async fn accept(
+        self,
+        name: &str,
+        password_hash: &str,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/company_member/accept.sql",
+            self.id,
+            name,
+            password_hash,
+            Utc::now()
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn patch(
+        self,
+        role: DBCompanyRole,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/company_member/patch.sql",
+            self.id,
+            role as DBCompanyRole
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn delete(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), DatabaseError> {
+        query_file!("sql/company_member/delete.sql", self.id)
+            .execute(&mut **transaction)
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn into_model(self) -> CompanyMember {
+        CompanyMember::from(self)
+    }
+}
@@ -0,0 +1,48 @@
+use sqlx::{query_file, query_file_as, Executor, Postgres};
+use uuid::Uuid;
+
+use crate::models::CompanyPromoStats;
+
+use super::DatabaseError;
+
+#[derive(Debug)]
+pub struct DBCompanyPromoStats {
+    pub promo_id: Uuid,
+    pub description: String,
+    pub active: bool,
+    pub activations_count: i64,
+    pub likes_count: i64,
+    pub comments_count: i64,
+    pub impressions_count: i64,
+    pub views_count: i64,
+    pub unique_viewers: i64,
+}
+
+impl DBCompanyPromoStats {
+    pub async fn get_all<'a, E>(company_id: Uuid, executor: E) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(
+            query_file_as!(Self, "sql/company_stats/promos.sql", company_id)
+                .fetch_all(executor)
+                .await?,
+        )
+    }
+
+    pub async fn unique_viewers<'a, E>(company_id: Uuid, executor: E) -> Result<i64, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(
+            query_file!("sql/company_stats/unique_viewers.sql", company_id)
+                .fetch_one(executor)
+                .await?
+                .unique_viewers,
+        )
+    }
+
+    pub fn into_model(self) -> CompanyPromoStats {
+        CompanyPromoStats::from(self)
+    }
+}
@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{query_file, query_file_as, Executor, Postgres};
+use uuid::Uuid;
+
+use crate::models::Event;
+
+use super::DatabaseError;
+
+#[derive(Debug)]
+pub struct DBEvent {
+    pub id: i64,
+    pub transaction_id: i64,
+    pub event: String,
+    pub company_id: Uuid,
+    pub promo_id: Uuid,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DBEvent {
+    /// Returns events ordered by the transaction that wrote them. Only
+    /// transactions older than every running one are read, so no event can
+    /// later commit behind `(transaction_id, id)`.
+    pub async fn get_after<'a, E>(
+        transaction_id: i64,
+        id: i64,
+        limit: i64,
+        executor: E,
+    ) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(
+            query_file_as!(Self, "sql/event/get_after.sql", transaction_id, id, limit)
+                .fetch_all(executor)
+                .await?,
+        )
+    }
+
+    /// Id of the oldest transaction that is still running. Events written by
+    /// it or later ones haven't been read by `get_after` yet.
+    pub async fn head<'a, E>(executor: E) -> Result<i64, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file!("sql/event/head.sql")
+            .fetch_one(executor)
+            .await?
+            .transaction_id)
+    }
+
+    pub async fn delete_older_than<'a, E>(
+        date: DateTime<Utc>,
+        executor: E,
+    ) -> Result<(), DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        query_file!("sql/event/delete_older_than.sql", date)
+            .execute(executor)
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn into_model(self) -> Event {
+        Event::from(self)
+    }
+}
@@ -1,20 +1,45 @@
 use thiserror::Error;
 
+mod api_key;
 mod comment;
 mod company;
+mod company_member;
+mod company_stats;
+mod event;
 mod like;
 mod promo;
 mod promo_activation;
+mod promo_code;
+mod promo_query;
+mod promo_revision;
+mod promo_view;
+mod session;
 mod token;
 mod user;
+mod webhook;
+mod webhook_delivery;
 
+pub use api_key::DBApiKey;
 pub use comment::DBComment;
 pub use company::DBCompany;
+pub use company_member::{DBCompanyMember, DBCompanyRole};
+pub use company_stats::DBCompanyPromoStats;
+pub use event::DBEvent;
 pub use like::DBLike;
-pub use promo::{DBPromo, DBPromoMode, DBTarget};
-pub use promo_activation::{DBCountryStats, DBPromoActivation};
+pub use promo::{DBPromo, DBPromoMode, DBPromoStatus, DBTarget};
+pub use promo_activation::{
+    DBActivationBucket, DBActivationHistoryEntry, DBActivationUsage, DBCountryStats,
+    DBPromoActivation, DBRedemptionStats,
+};
+pub use promo_code::DBPromoCode;
+pub use promo_query::{DBPromoQuery, DBPromoSortField, DBSortOrder};
+pub use promo_revision::{DBPromoRevision, DBRevisionAuthor};
+pub use promo_view::{DBPromoView, DBPromoViewKind};
+pub use session::DBSession;
 pub use token::DBToken;
 pub use user::{DBUser, DBUserTargetSettings};
+pub use webhook::DBWebhook;
+pub use webhook_delivery::{DBWebhookDelivery, DBWebhookDeliveryStatus};
 
 pub struct Email(pub String);
 
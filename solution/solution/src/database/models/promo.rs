@@ -9,13 +9,13 @@ use sqlx::{
 use uuid::Uuid;
 
 use crate::{
-    models::{Promo, PromoTarget},
+    models::{PageRequest, Promo, PromoPatch, PromoSnapshot, PromoTarget},
     util::values::{MAX_DATETIME, MIN_DATETIME},
 };
 
-use super::DatabaseError;
+use super::{DBPromoCode, DatabaseError};
 
-#[derive(FromRow, Type, Debug)]
+#[derive(FromRow, Type, Clone, Debug)]
 #[sqlx(type_name = "target")]
 pub struct DBTarget {
     pub age_from: Option<i32>,
@@ -35,14 +35,34 @@ impl From<PromoTarget> for DBTarget {
     }
 }
 
-#[derive(Type, Deserialize, Serialize, PartialEq, Eq, Debug)]
+#[derive(Type, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
 #[sqlx(type_name = "promo_mode")]
 pub enum DBPromoMode {
     COMMON,
     UNIQUE,
 }
 
-#[derive(Debug)]
+#[derive(Type, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "promo_status")]
+pub enum DBPromoStatus {
+    DRAFT,
+    PUBLISHED,
+    PAUSED,
+    ARCHIVED,
+}
+
+impl DBPromoStatus {
+    pub fn can_become(self, status: Self) -> bool {
+        matches!(
+            (self, status),
+            (Self::DRAFT | Self::PAUSED, Self::PUBLISHED)
+                | (Self::PUBLISHED, Self::PAUSED)
+                | (Self::DRAFT | Self::PUBLISHED | Self::PAUSED, Self::ARCHIVED)
+        )
+    }
+}
+
+#[derive(FromRow, Debug)]
 pub struct DBPromo {
     pub id: Uuid,
     pub company_id: Uuid,
@@ -55,19 +75,25 @@ pub struct DBPromo {
     pub active_until: DateTime<Utc>,
     pub mode: DBPromoMode,
     pub promo_common: Option<String>,
-    pub promo_unique: Vec<String>,
+    pub unique_count: i64,
+    pub unique_available: i64,
     pub like_count: i32,
     pub used_count: i32,
     pub comment_count: i32,
     pub active: bool,
+    pub per_user_limit: Option<i32>,
+    pub cooldown: Option<i32>,
+    pub max_per_day: Option<i32>,
+    pub status: DBPromoStatus,
 }
 
 impl DBPromo {
     pub async fn insert(
         self,
+        codes: &[String],
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<Self, DatabaseError> {
-        Ok(query_file_as!(
+        let mut promo = query_file_as!(
             Self,
             "sql/promo/insert.sql",
             self.id,
@@ -80,198 +106,67 @@ impl DBPromo {
             self.active_until,
             self.mode as DBPromoMode,
             self.promo_common,
-            &self.promo_unique,
             self.like_count,
             self.used_count,
             self.comment_count,
-            self.active
+            self.active,
+            self.per_user_limit,
+            self.cooldown,
+            self.max_per_day,
+            self.status as DBPromoStatus
         )
         .fetch_one(&mut **transaction)
-        .await?)
-    }
+        .await?;
 
-    pub async fn get_pageable<'a, E>(
-        company_id: Uuid,
-        limit: i64,
-        offset: i64,
-        sort_by: &str,
-        countries: &Option<Vec<String>>,
-        executor: E,
-    ) -> Result<(Vec<DBPromo>, i64), DatabaseError>
-    where
-        E: Executor<'a, Database = Postgres> + Copy,
-    {
-        let promos = match (countries, sort_by) {
-            (Some(countries), "id") => {
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_id_by_countries.sql",
-                    company_id,
-                    countries,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await
-            }
-            (Some(countries), r#""active_from""#) => {
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_active_from_by_countries.sql",
-                    company_id,
-                    countries,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await
-            }
-            (Some(countries), r#""active_until""#) => {
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_active_until_by_countries.sql",
-                    company_id,
-                    countries,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await
-            }
-            (None, "id") => {
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_id.sql",
-                    company_id,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await
-            }
-            (None, r#""active_from""#) => {
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_active_from.sql",
-                    company_id,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await
-            }
-            (None, r#""active_until""#) => {
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_active_until.sql",
-                    company_id,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await
-            }
-            _ => unreachable!(),
-        }?;
-        let count = if let Some(countries) = countries {
-            query_file!("sql/promo/count_by_countries.sql", company_id, countries)
-                .fetch_one(executor)
-                .await?
-                .count
-        } else {
-            query_file!("sql/promo/count.sql", company_id)
-                .fetch_one(executor)
-                .await?
-                .count
+        if !codes.is_empty() {
+            let added = DBPromoCode::insert_many(promo.id, codes, transaction).await? as i64;
+            promo.unique_count = added;
+            promo.unique_available = added;
         }
-        .unwrap();
 
-        return Ok((promos, count));
+        Ok(promo)
     }
 
-    pub async fn get_pageable_user<'a, E>(
-        limit: i64,
-        offset: i64,
-        country: &str,
+    pub async fn search_user<'a, E>(
+        page: &PageRequest,
+        user_id: Uuid,
+        query: &str,
         category: Option<&str>,
         active: Option<bool>,
         executor: E,
-    ) -> Result<(Vec<Self>, i64), DatabaseError>
+    ) -> Result<(Vec<Self>, Option<i64>), DatabaseError>
     where
         E: Executor<'a, Database = Postgres> + Copy,
     {
-        let (promos, count) = match (category, active) {
-            (Some(category), Some(active)) => (
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_user_by_category_active.sql",
-                    country,
-                    category,
-                    active,
-                    limit,
-                    offset,
-                )
-                .fetch_all(executor)
-                .await?,
-                query_file!(
-                    "sql/promo/count_user_by_category_active.sql",
-                    country,
-                    category,
-                    active
-                )
-                .fetch_one(executor)
-                .await?
-                .count,
-            ),
-            (Some(category), None) => (
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_user_by_category.sql",
-                    country,
-                    category,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await?,
-                query_file!("sql/promo/count_user_by_category.sql", country, category)
-                    .fetch_one(executor)
-                    .await?
-                    .count,
-            ),
-            (None, Some(active)) => (
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_user_by_active.sql",
-                    country,
-                    active,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await?,
-                query_file!("sql/promo/count_user_by_active.sql", country, active)
-                    .fetch_one(executor)
-                    .await?
-                    .count,
-            ),
-            (None, None) => (
-                query_file_as!(
-                    Self,
-                    "sql/promo/get_pageable_user.sql",
-                    country,
-                    limit,
-                    offset
-                )
-                .fetch_all(executor)
-                .await?,
-                query_file!("sql/promo/count_user.sql", country)
-                    .fetch_one(executor)
-                    .await?
-                    .count,
-            ),
-        };
-        Ok((promos, count.unwrap()))
+        let promos = query_file_as!(
+            Self,
+            "sql/promo/search_user.sql",
+            user_id,
+            query,
+            category,
+            active,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(executor)
+        .await?;
+
+        if !page.with_total {
+            return Ok((promos, None));
+        }
+
+        let count = query_file!(
+            "sql/promo/count_search_user.sql",
+            user_id,
+            query,
+            category,
+            active
+        )
+        .fetch_one(executor)
+        .await?
+        .count;
+
+        Ok((promos, count))
     }
 
     pub async fn get_by_id<'a, E>(id: Uuid, executor: E) -> Result<Option<Self>, DatabaseError>
@@ -283,31 +178,118 @@ impl DBPromo {
             .await?)
     }
 
+    /// Locks the promo row until the end of the transaction. Queries run after
+    /// it see every change committed by the previous holder of the lock.
+    /// Returns whether the promo exists.
+    pub async fn lock(
+        id: Uuid,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<bool, DatabaseError> {
+        Ok(query_file!("sql/promo/lock.sql", id)
+            .fetch_optional(&mut **transaction)
+            .await?
+            .is_some())
+    }
+
+    /// Evaluates the promo's target with `target_matches`, the same rules the
+    /// user feed is filtered with.
+    pub async fn matches_target<'a, E>(
+        id: Uuid,
+        user_id: Uuid,
+        executor: E,
+    ) -> Result<bool, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file!("sql/promo/matches_target.sql", id, user_id)
+            .fetch_optional(executor)
+            .await?
+            .is_some_and(|row| row.matches))
+    }
+
     pub async fn patch(
         self,
-        description: Option<String>,
-        image_url: Option<String>,
-        target: Option<DBTarget>,
-        max_count: Option<i32>,
-        active_from: Option<DateTime<Utc>>,
-        active_until: Option<DateTime<Utc>>,
+        patch: &PromoPatch,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<Self, DatabaseError> {
         Ok(query_file_as!(
             Self,
             "sql/promo/patch.sql",
             self.id,
-            description,
-            image_url,
-            target as Option<DBTarget>,
-            max_count,
-            active_from,
-            active_until
+            patch.description,
+            patch.image_url,
+            patch.target.clone().map(PromoTarget::into_db) as Option<DBTarget>,
+            patch.max_count,
+            patch.active_from,
+            patch.active_until,
+            patch.per_user_limit,
+            patch.cooldown,
+            patch.max_per_day
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    /// Overwrites every editable field, unlike `patch` which keeps the
+    /// current value of omitted ones.
+    pub async fn restore(
+        self,
+        snapshot: PromoSnapshot,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/promo/restore.sql",
+            self.id,
+            snapshot.description,
+            snapshot.image_url,
+            snapshot.target.into_db() as DBTarget,
+            snapshot.max_count,
+            snapshot.active_from.unwrap_or(MIN_DATETIME),
+            snapshot.active_until.unwrap_or(MAX_DATETIME),
+            snapshot.per_user_limit,
+            snapshot.cooldown,
+            snapshot.max_per_day
         )
         .fetch_one(&mut **transaction)
         .await?)
     }
 
+    /// Moves the promo to `status` unless its status has changed since it was
+    /// fetched. Returns whether the promo was updated.
+    pub async fn set_status(
+        &self,
+        status: DBPromoStatus,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<bool, DatabaseError> {
+        Ok(query_file!(
+            "sql/promo/set_status.sql",
+            self.id,
+            self.status as DBPromoStatus,
+            status as DBPromoStatus
+        )
+        .execute(&mut **transaction)
+        .await?
+        .rows_affected()
+            > 0)
+    }
+
+    pub async fn delete(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), DatabaseError> {
+        query_file!("sql/promo/delete.sql", self.id)
+            .execute(&mut **transaction)
+            .await?;
+        Ok(())
+    }
+
+    /// Only published promos are visible to users, drafts and paused or
+    /// archived promos only to the company that owns them.
+    pub fn is_public(&self) -> bool {
+        self.status == DBPromoStatus::PUBLISHED
+    }
+
     pub fn into_model(self) -> Promo {
         Promo::from(self)
     }
@@ -327,11 +309,16 @@ impl From<Promo> for DBPromo {
             active_until: promo.active_until.unwrap_or(MAX_DATETIME),
             mode: promo.mode,
             promo_common: promo.promo_common,
-            promo_unique: promo.promo_unique.unwrap_or(vec![]),
+            unique_count: promo.promo_unique_count.unwrap_or(0),
+            unique_available: promo.promo_unique_available.unwrap_or(0),
             like_count: promo.like_count,
             used_count: promo.used_count,
             comment_count: promo.comment_count,
             active: promo.active,
+            per_user_limit: promo.per_user_limit,
+            cooldown: promo.cooldown,
+            max_per_day: promo.max_per_day,
+            status: promo.status,
         }
     }
 }
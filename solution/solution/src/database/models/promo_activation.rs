@@ -2,9 +2,9 @@ use chrono::{DateTime, Utc};
 use sqlx::{query_file, query_file_as, Executor, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::models::PromoStatsCountry;
+use crate::models::{PageRequest, PromoStatsCountry, StatsBucket};
 
-use super::{DBPromo, DBPromoMode, DBTarget, DatabaseError};
+use super::{DBPromo, DBPromoMode, DBPromoStatus, DBTarget, DatabaseError};
 
 #[derive(Debug)]
 pub struct DBPromoActivation {
@@ -12,6 +12,7 @@ pub struct DBPromoActivation {
     pub promo_id: Uuid,
     pub promo: String,
     pub date: DateTime<Utc>,
+    pub redeemed_at: Option<DateTime<Utc>>,
 }
 
 impl DBPromoActivation {
@@ -32,12 +33,24 @@ impl DBPromoActivation {
         .await?)
     }
 
+    /// Issues the next available code of a UNIQUE promo, or returns the
+    /// activation the user already has. Requests of the same user are
+    /// serialized first, so a request that would fail on the one code per
+    /// user rule never holds a code other users skip over.
     pub async fn activate_unique(
         user_id: Uuid,
         promo_id: Uuid,
         date: DateTime<Utc>,
         transaction: &mut Transaction<'_, Postgres>,
-    ) -> Result<Self, DatabaseError> {
+    ) -> Result<Option<Self>, DatabaseError> {
+        query_file!("sql/promo_activation/lock_user.sql", promo_id, user_id)
+            .fetch_one(&mut **transaction)
+            .await?;
+
+        if let Some(activation) = Self::get_by_ids(user_id, promo_id, &mut **transaction).await? {
+            return Ok(Some(activation));
+        }
+
         Ok(query_file_as!(
             Self,
             "sql/promo_activation/unique.sql",
@@ -45,7 +58,7 @@ impl DBPromoActivation {
             promo_id,
             date
         )
-        .fetch_one(&mut **transaction)
+        .fetch_optional(&mut **transaction)
         .await?)
     }
 
@@ -67,34 +80,192 @@ impl DBPromoActivation {
         .await?)
     }
 
+    /// Finds the activations a UNIQUE code of the company's promos was issued
+    /// in, optionally only the one of `promo_id`. COMMON codes are shared by
+    /// every user, so they can't tell whose activation is being redeemed.
+    pub async fn get_by_code<'a, E>(
+        company_id: Uuid,
+        code: &str,
+        promo_id: Option<Uuid>,
+        executor: E,
+    ) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(
+            Self,
+            "sql/promo_activation/get_by_code.sql",
+            company_id,
+            code,
+            promo_id
+        )
+        .fetch_all(executor)
+        .await?)
+    }
+
+    /// Marks the activation of `code` as redeemed unless it already is.
+    pub async fn redeem(
+        promo_id: Uuid,
+        code: &str,
+        date: DateTime<Utc>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/promo_activation/redeem.sql",
+            promo_id,
+            code,
+            date
+        )
+        .fetch_optional(&mut **transaction)
+        .await?)
+    }
+
     pub async fn get_history<'a, E>(
         user_id: Uuid,
-        limit: i64,
-        offset: i64,
+        page: &PageRequest,
         executor: E,
-    ) -> Result<(Vec<DBPromo>, i64), DatabaseError>
+    ) -> Result<(Vec<DBActivationHistoryEntry>, Option<i64>), DatabaseError>
     where
         E: Executor<'a, Database = Postgres> + Copy,
     {
-        Ok((
+        let entries = query_file_as!(
+            DBActivationHistoryEntry,
+            "sql/promo_activation/history_pageable.sql",
+            user_id,
+            page.limit,
+            page.offset,
+            page.cursor_date(),
+            page.cursor_id()
+        )
+        .fetch_all(executor)
+        .await?;
+
+        if !page.with_total {
+            return Ok((entries, None));
+        }
+
+        let count = query_file!("sql/promo_activation/history_count.sql", user_id)
+            .fetch_one(executor)
+            .await?
+            .count;
+
+        Ok((entries, count))
+    }
+}
+
+#[derive(Debug)]
+pub struct DBRedemptionStats {
+    pub issued_count: i64,
+    pub redeemed_count: i64,
+}
+
+impl DBRedemptionStats {
+    pub async fn get<'a, E>(promo_id: Uuid, executor: E) -> Result<Self, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(
+            query_file_as!(Self, "sql/promo_activation/redemptions.sql", promo_id)
+                .fetch_one(executor)
+                .await?,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct DBActivationUsage {
+    pub user_activations: i64,
+    pub last_activation: Option<DateTime<Utc>>,
+    pub today_activations: i64,
+}
+
+impl DBActivationUsage {
+    /// Locks the promo row for the rest of the transaction, so the returned
+    /// numbers stay accurate until the activation is inserted. The numbers are
+    /// read by a separate statement, as a statement taking the lock would
+    /// still count from the snapshot it started with.
+    pub async fn get_locked(
+        user_id: Uuid,
+        promo_id: Uuid,
+        date: DateTime<Utc>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<Self>, DatabaseError> {
+        if !DBPromo::lock(promo_id, transaction).await? {
+            return Ok(None);
+        }
+
+        Ok(Some(
             query_file_as!(
-                DBPromo,
-                "sql/promo_activation/history_pageable.sql",
+                Self,
+                "sql/promo_activation/usage.sql",
                 user_id,
-                limit,
-                offset
+                promo_id,
+                date
             )
-            .fetch_all(executor)
+            .fetch_one(&mut **transaction)
             .await?,
-            query_file!("sql/promo_activation/history_count.sql", user_id)
-                .fetch_one(executor)
-                .await?
-                .count
-                .unwrap(),
         ))
     }
 }
 
+#[derive(Debug)]
+pub struct DBActivationHistoryEntry {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub company_name: Option<String>,
+    pub description: String,
+    pub image_url: Option<String>,
+    pub target: DBTarget,
+    pub max_count: i32,
+    pub active_from: DateTime<Utc>,
+    pub active_until: DateTime<Utc>,
+    pub mode: DBPromoMode,
+    pub promo_common: Option<String>,
+    pub unique_count: i64,
+    pub unique_available: i64,
+    pub like_count: i32,
+    pub used_count: i32,
+    pub comment_count: i32,
+    pub active: bool,
+    pub per_user_limit: Option<i32>,
+    pub cooldown: Option<i32>,
+    pub max_per_day: Option<i32>,
+    pub status: DBPromoStatus,
+    pub activated_at: DateTime<Utc>,
+}
+
+impl DBActivationHistoryEntry {
+    pub fn into_parts(self) -> (DBPromo, DateTime<Utc>) {
+        (
+            DBPromo {
+                id: self.id,
+                company_id: self.company_id,
+                company_name: self.company_name,
+                description: self.description,
+                image_url: self.image_url,
+                target: self.target,
+                max_count: self.max_count,
+                active_from: self.active_from,
+                active_until: self.active_until,
+                mode: self.mode,
+                promo_common: self.promo_common,
+                unique_count: self.unique_count,
+                unique_available: self.unique_available,
+                like_count: self.like_count,
+                used_count: self.used_count,
+                comment_count: self.comment_count,
+                active: self.active,
+                per_user_limit: self.per_user_limit,
+                cooldown: self.cooldown,
+                max_per_day: self.max_per_day,
+                status: self.status,
+            },
+            self.activated_at,
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct DBCountryStats {
     pub country: Option<String>,
@@ -113,7 +284,61 @@ impl DBCountryStats {
         )
     }
 
+    pub async fn get_top_for_company<'a, E>(
+        company_id: Uuid,
+        limit: i64,
+        executor: E,
+    ) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(
+            Self,
+            "sql/company_stats/top_countries.sql",
+            company_id,
+            limit
+        )
+        .fetch_all(executor)
+        .await?)
+    }
+
     pub fn into_model(self) -> PromoStatsCountry {
         PromoStatsCountry::from(self)
     }
 }
+
+#[derive(Debug)]
+pub struct DBActivationBucket {
+    pub bucket: DateTime<Utc>,
+    pub country: Option<String>,
+    pub age_band: Option<String>,
+    pub activations_count: i64,
+}
+
+impl DBActivationBucket {
+    pub async fn get_all<'a, E>(
+        promo_id: Uuid,
+        bucket: StatsBucket,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        by_country: bool,
+        by_age: bool,
+        executor: E,
+    ) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(
+            Self,
+            "sql/promo_activation/timeseries.sql",
+            promo_id,
+            <&str>::from(bucket),
+            from,
+            to,
+            by_country,
+            by_age
+        )
+        .fetch_all(executor)
+        .await?)
+    }
+}
@@ -0,0 +1,49 @@
+use sqlx::{query_file, Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::PageRequest;
+
+use super::DatabaseError;
+
+pub struct DBPromoCode;
+
+impl DBPromoCode {
+    /// Appends codes to the pool of a UNIQUE promo, skipping the ones it
+    /// already has. Returns how many codes were actually added. Codes are
+    /// numbered after the last one in the pool, so the promo has to be locked
+    /// with `DBPromo::lock` unless it was created in the same transaction.
+    pub async fn insert_many(
+        promo_id: Uuid,
+        codes: &[String],
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<u64, DatabaseError> {
+        Ok(
+            query_file!("sql/promo_code/insert_many.sql", promo_id, codes)
+                .execute(&mut **transaction)
+                .await?
+                .rows_affected(),
+        )
+    }
+
+    /// Lists a promo's codes in the order they are issued in.
+    pub async fn get_pageable<'a, E>(
+        promo_id: Uuid,
+        page: &PageRequest,
+        executor: E,
+    ) -> Result<Vec<String>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file!(
+            "sql/promo_code/get_pageable.sql",
+            promo_id,
+            page.limit,
+            page.offset
+        )
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .map(|row| row.code)
+        .collect())
+    }
+}
@@ -0,0 +1,511 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::models::{Cursor, Page, PageRequest};
+
+use super::{DBPromo, DBPromoMode, DBPromoStatus, DatabaseError};
+
+const PROMO_COLUMNS: &str = "SELECT promos.id, company_id, companies.name AS company_name, \
+     description, image_url, target, max_count, active_from, active_until, mode, promo_common, \
+     promo_code_count(promos.id) AS unique_count, \
+     promo_code_count(promos.id, 'AVAILABLE') AS unique_available, like_count, used_count, \
+     comment_count, active, per_user_limit, cooldown, max_per_day, status \
+     FROM promos LEFT JOIN companies ON companies.id = company_id";
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum DBPromoSortField {
+    #[default]
+    Id,
+    ActiveFrom,
+    ActiveUntil,
+}
+
+impl DBPromoSortField {
+    fn column(self) -> Option<&'static str> {
+        match self {
+            Self::Id => None,
+            Self::ActiveFrom => Some("active_from"),
+            Self::ActiveUntil => Some("active_until"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DBSortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl DBSortOrder {
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+
+    fn comparison(self) -> &'static str {
+        match self {
+            Self::Asc => " > ",
+            Self::Desc => " < ",
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct DBPromoQuery {
+    company_id: Option<Uuid>,
+    target_user_id: Option<Uuid>,
+    liked_by: Option<Uuid>,
+    countries: Option<Vec<String>>,
+    category: Option<String>,
+    active: Option<bool>,
+    mode: Option<DBPromoMode>,
+    status: Option<DBPromoStatus>,
+    active_since: Option<DateTime<Utc>>,
+    active_till: Option<DateTime<Utc>>,
+    sort_by: DBPromoSortField,
+    order: DBSortOrder,
+}
+
+impl DBPromoQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn company(mut self, company_id: Uuid) -> Self {
+        self.company_id = Some(company_id);
+        self
+    }
+
+    pub fn targeting(mut self, user_id: Uuid) -> Self {
+        self.target_user_id = Some(user_id);
+        self
+    }
+
+    pub fn liked_by(mut self, user_id: Uuid) -> Self {
+        self.liked_by = Some(user_id);
+        self
+    }
+
+    pub fn countries(mut self, countries: Option<Vec<String>>) -> Self {
+        self.countries = countries.map(|v| {
+            v.into_iter()
+                .map(|country| country.to_lowercase())
+                .collect()
+        });
+        self
+    }
+
+    pub fn category(mut self, category: Option<String>) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn active(mut self, active: Option<bool>) -> Self {
+        self.active = active;
+        self
+    }
+
+    pub fn mode(mut self, mode: Option<DBPromoMode>) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn status(mut self, status: Option<DBPromoStatus>) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Keeps only promos whose activity period overlaps `[since, till]`.
+    pub fn active_between(
+        mut self,
+        since: Option<DateTime<Utc>>,
+        till: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.active_since = since;
+        self.active_till = till;
+        self
+    }
+
+    pub fn sort(mut self, sort_by: DBPromoSortField, order: DBSortOrder) -> Self {
+        self.sort_by = sort_by;
+        self.order = order;
+        self
+    }
+
+    pub fn sort_by(&self) -> DBPromoSortField {
+        self.sort_by
+    }
+
+    pub fn cursor(&self, promo: &DBPromo) -> Cursor {
+        match self.sort_by {
+            DBPromoSortField::Id => Cursor::new(promo.id),
+            DBPromoSortField::ActiveFrom => Cursor::with_date(promo.active_from, promo.id),
+            DBPromoSortField::ActiveUntil => Cursor::with_date(promo.active_until, promo.id),
+        }
+    }
+
+    pub async fn fetch_page<'a, E>(
+        &self,
+        page: &PageRequest,
+        executor: E,
+    ) -> Result<Page<DBPromo>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres> + Copy,
+    {
+        let mut builder = self.select(page.cursor, page.limit, page.offset);
+
+        let promos = builder
+            .build_query_as::<DBPromo>()
+            .fetch_all(executor)
+            .await?;
+
+        let count = if page.with_total {
+            Some(self.count(executor).await?)
+        } else {
+            None
+        };
+
+        Ok(Page::new(promos, count, page, |promo| self.cursor(promo)))
+    }
+
+    pub async fn fetch_all<'a, E>(
+        &self,
+        limit: i64,
+        offset: i64,
+        executor: E,
+    ) -> Result<Vec<DBPromo>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        let mut builder = self.select(None, limit, offset);
+
+        Ok(builder
+            .build_query_as::<DBPromo>()
+            .fetch_all(executor)
+            .await?)
+    }
+
+    pub async fn count<'a, E>(&self, executor: E) -> Result<i64, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        let mut builder = self.select_count();
+
+        Ok(builder
+            .build_query_scalar::<i64>()
+            .fetch_one(executor)
+            .await?)
+    }
+
+    fn select(
+        &self,
+        cursor: Option<Cursor>,
+        limit: i64,
+        offset: i64,
+    ) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new(PROMO_COLUMNS);
+        self.push_filters(&mut builder);
+        self.push_keyset(&mut builder, cursor);
+        self.push_ordering(&mut builder);
+        builder
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        builder
+    }
+
+    fn select_count(&self) -> QueryBuilder<'static, Postgres> {
+        let mut builder = QueryBuilder::new("SELECT count(*) FROM promos");
+        self.push_filters(&mut builder);
+        builder
+    }
+
+    fn push_filters(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push(" WHERE TRUE");
+
+        if let Some(company_id) = self.company_id {
+            builder
+                .push(" AND promos.company_id = ")
+                .push_bind(company_id);
+        }
+
+        if let Some(user_id) = self.target_user_id {
+            builder
+                .push(" AND target_matches(target, (SELECT other FROM users WHERE id = ")
+                .push_bind(user_id)
+                .push("), (SELECT user_interests(")
+                .push_bind(user_id)
+                .push(")))");
+        }
+
+        if let Some(user_id) = self.liked_by {
+            builder
+                .push(" AND EXISTS (SELECT 1 FROM likes WHERE likes.promo_id = promos.id")
+                .push(" AND likes.user_id = ")
+                .push_bind(user_id)
+                .push(")");
+        }
+
+        if let Some(countries) = &self.countries {
+            builder
+                .push(" AND ((target).country IS NULL OR lower((target).country) = ANY (")
+                .push_bind(countries.clone())
+                .push("))");
+        }
+
+        if let Some(category) = &self.category {
+            builder
+                .push(" AND lower(")
+                .push_bind(category.clone())
+                .push(") = ANY (lower((target).categories::text)::text[])");
+        }
+
+        if let Some(active) = self.active {
+            builder.push(" AND promos.active = ").push_bind(active);
+        }
+
+        if let Some(mode) = self.mode {
+            builder.push(" AND promos.mode = ").push_bind(mode);
+        }
+
+        if let Some(status) = self.status {
+            builder.push(" AND promos.status = ").push_bind(status);
+        }
+
+        if let Some(since) = self.active_since {
+            builder
+                .push(" AND promos.active_until >= ")
+                .push_bind(since);
+        }
+
+        if let Some(till) = self.active_till {
+            builder.push(" AND promos.active_from <= ").push_bind(till);
+        }
+    }
+
+    fn push_keyset(&self, builder: &mut QueryBuilder<'_, Postgres>, cursor: Option<Cursor>) {
+        let Some(cursor) = cursor else {
+            return;
+        };
+
+        match (self.sort_by.column(), cursor.date) {
+            (Some(column), Some(date)) => {
+                builder
+                    .push(format!(" AND ({}, promos.id)", column))
+                    .push(self.order.comparison())
+                    .push("(")
+                    .push_bind(date)
+                    .push(", ")
+                    .push_bind(cursor.id)
+                    .push(")");
+            }
+            _ => {
+                builder
+                    .push(" AND promos.id")
+                    .push(self.order.comparison())
+                    .push_bind(cursor.id);
+            }
+        }
+    }
+
+    fn push_ordering(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        let order = self.order.keyword();
+
+        builder.push(" ORDER BY ");
+        if let Some(column) = self.sort_by.column() {
+            builder.push(format!("{} {}, ", column, order));
+        }
+        builder.push(format!("promos.id {}", order));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    // In the order `push_filters` adds them, `{}` stands for a bind
+    const FILTERS: [&str; 10] = [
+        " AND promos.company_id = {}",
+        " AND target_matches(target, (SELECT other FROM users WHERE id = {}), \
+         (SELECT user_interests({})))",
+        " AND EXISTS (SELECT 1 FROM likes WHERE likes.promo_id = promos.id \
+         AND likes.user_id = {})",
+        " AND ((target).country IS NULL OR lower((target).country) = ANY ({}))",
+        " AND lower({}) = ANY (lower((target).categories::text)::text[])",
+        " AND promos.active = {}",
+        " AND promos.mode = {}",
+        " AND promos.status = {}",
+        " AND promos.active_until >= {}",
+        " AND promos.active_from <= {}",
+    ];
+
+    fn date() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()
+    }
+
+    fn query(filters: u32) -> DBPromoQuery {
+        let enabled = |filter: usize| filters & (1 << filter) != 0;
+        let id = Uuid::now_v7();
+
+        let mut query = DBPromoQuery::new();
+        if enabled(0) {
+            query = query.company(id);
+        }
+        if enabled(1) {
+            query = query.targeting(id);
+        }
+        if enabled(2) {
+            query = query.liked_by(id);
+        }
+        query
+            .countries(enabled(3).then(|| vec!["RU".to_string()]))
+            .category(enabled(4).then(|| "food".to_string()))
+            .active(enabled(5).then_some(true))
+            .mode(enabled(6).then_some(DBPromoMode::UNIQUE))
+            .status(enabled(7).then_some(DBPromoStatus::PUBLISHED))
+            .active_between(enabled(8).then(date), enabled(9).then(date))
+    }
+
+    /// Numbers every `{}` in `fragment` starting with `next`.
+    fn numbered(fragment: &str, next: &mut usize) -> String {
+        let mut sql = String::new();
+        for (i, part) in fragment.split("{}").enumerate() {
+            if i > 0 {
+                sql.push_str(&format!("${next}"));
+                *next += 1;
+            }
+            sql.push_str(part);
+        }
+        sql
+    }
+
+    fn expected_filters(filters: u32, next: &mut usize) -> String {
+        let mut sql = " WHERE TRUE".to_string();
+        for (i, fragment) in FILTERS.iter().enumerate() {
+            if filters & (1 << i) != 0 {
+                sql.push_str(&numbered(fragment, next));
+            }
+        }
+        sql
+    }
+
+    #[test]
+    fn builds_default_query() {
+        let query = DBPromoQuery::new();
+
+        assert_eq!(
+            query.select(None, 10, 0).sql(),
+            format!("{PROMO_COLUMNS} WHERE TRUE ORDER BY promos.id DESC LIMIT $1 OFFSET $2")
+        );
+        assert_eq!(
+            query.select_count().sql(),
+            "SELECT count(*) FROM promos WHERE TRUE"
+        );
+    }
+
+    #[test]
+    fn builds_company_list_query() {
+        let query = DBPromoQuery::new()
+            .company(Uuid::now_v7())
+            .countries(Some(vec!["ru".to_string(), "FR".to_string()]))
+            .sort(DBPromoSortField::ActiveUntil, DBSortOrder::Asc);
+        let cursor = Cursor::with_date(date(), Uuid::now_v7());
+
+        assert_eq!(
+            query.select(Some(cursor), 10, 0).sql(),
+            format!(
+                "{PROMO_COLUMNS} WHERE TRUE AND promos.company_id = $1 \
+                 AND ((target).country IS NULL OR lower((target).country) = ANY ($2)) \
+                 AND (active_until, promos.id) > ($3, $4) \
+                 ORDER BY active_until ASC, promos.id ASC LIMIT $5 OFFSET $6"
+            )
+        );
+        assert_eq!(
+            query.countries,
+            Some(vec!["ru".to_string(), "fr".to_string()])
+        );
+    }
+
+    #[test]
+    fn builds_every_combination() {
+        let id = Uuid::now_v7();
+        let cursors = [
+            None,
+            Some(Cursor::new(id)),
+            Some(Cursor::with_date(date(), id)),
+        ];
+
+        for filters in 0..1 << FILTERS.len() {
+            for sort_by in [
+                DBPromoSortField::Id,
+                DBPromoSortField::ActiveFrom,
+                DBPromoSortField::ActiveUntil,
+            ] {
+                for order in [DBSortOrder::Asc, DBSortOrder::Desc] {
+                    for cursor in cursors {
+                        let query = query(filters).sort(sort_by, order);
+
+                        let (keyword, comparison) = match order {
+                            DBSortOrder::Asc => ("ASC", ">"),
+                            DBSortOrder::Desc => ("DESC", "<"),
+                        };
+                        let column = match sort_by {
+                            DBPromoSortField::Id => None,
+                            DBPromoSortField::ActiveFrom => Some("active_from"),
+                            DBPromoSortField::ActiveUntil => Some("active_until"),
+                        };
+
+                        let mut next = 1;
+                        let mut expected = PROMO_COLUMNS.to_string();
+                        expected.push_str(&expected_filters(filters, &mut next));
+                        match (column, cursor) {
+                            (_, None) => {}
+                            (Some(column), Some(Cursor { date: Some(_), .. })) => expected
+                                .push_str(&numbered(
+                                    &format!(
+                                        " AND ({column}, promos.id) {comparison} ({{}}, {{}})"
+                                    ),
+                                    &mut next,
+                                )),
+                            (_, Some(_)) => expected.push_str(&numbered(
+                                &format!(" AND promos.id {comparison} {{}}"),
+                                &mut next,
+                            )),
+                        }
+                        expected.push_str(" ORDER BY ");
+                        if let Some(column) = column {
+                            expected.push_str(&format!("{column} {keyword}, "));
+                        }
+                        expected.push_str(&format!("promos.id {keyword}"));
+                        expected.push_str(&numbered(" LIMIT {} OFFSET {}", &mut next));
+
+                        assert_eq!(
+                            query.select(cursor, 10, 0).sql(),
+                            expected,
+                            "filters {filters:#012b}, {sort_by:?} {order:?}, cursor {cursor:?}"
+                        );
+                    }
+                }
+            }
+
+            let mut next = 1;
+            assert_eq!(
+                query(filters).select_count().sql(),
+                format!(
+                    "SELECT count(*) FROM promos{}",
+                    expected_filters(filters, &mut next)
+                ),
+                "filters {filters:#012b}"
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{prelude::Type, query_file, query_file_as, Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::{PageRequest, PromoRevision};
+
+use super::DatabaseError;
+
+#[derive(Type, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "revision_author")]
+pub enum DBRevisionAuthor {
+    COMPANY,
+    MEMBER,
+    INTEGRATION,
+}
+
+#[derive(Debug)]
+pub struct DBPromoRevision {
+    pub id: Uuid,
+    pub promo_id: Uuid,
+    pub revision: i32,
+    pub author: DBRevisionAuthor,
+    pub member_id: Option<Uuid>,
+    pub restored_from: Option<i32>,
+    pub changes: Value,
+    pub snapshot: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DBPromoRevision {
+    /// Numbers revisions per promo. Callers must hold `DBPromo::lock` since
+    /// before they read the promo the revision is diffed against, unless it
+    /// was created in the same transaction.
+    pub async fn insert(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/promo_revision/insert.sql",
+            self.id,
+            self.promo_id,
+            self.author as DBRevisionAuthor,
+            self.member_id,
+            self.restored_from,
+            self.changes,
+            self.snapshot,
+            self.created_at
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn get_pageable<'a, E>(
+        promo_id: Uuid,
+        page: &PageRequest,
+        executor: E,
+    ) -> Result<(Vec<Self>, Option<i64>), DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres> + Copy,
+    {
+        let revisions = query_file_as!(
+            Self,
+            "sql/promo_revision/get_pageable.sql",
+            promo_id,
+            page.limit,
+            page.offset,
+            page.cursor_id()
+        )
+        .fetch_all(executor)
+        .await?;
+
+        if !page.with_total {
+            return Ok((revisions, None));
+        }
+
+        let count = query_file!("sql/promo_revision/count.sql", promo_id)
+            .fetch_one(executor)
+            .await?
+            .count;
+
+        Ok((revisions, count))
+    }
+
+    pub async fn get_by_revision<'a, E>(
+        promo_id: Uuid,
+        revision: i32,
+        executor: E,
+    ) -> Result<Option<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(
+            Self,
+            "sql/promo_revision/get_by_revision.sql",
+            promo_id,
+            revision
+        )
+        .fetch_optional(executor)
+        .await?)
+    }
+
+    pub fn into_model(self) -> PromoRevision {
+        PromoRevision::from(self)
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{prelude::Type, query_file, Executor, Postgres};
+use strum_macros::{Display, IntoStaticStr};
+use uuid::Uuid;
+
+use super::DatabaseError;
+
+#[derive(
+    Type, Deserialize, Serialize, Display, IntoStaticStr, Clone, Copy, PartialEq, Eq, Debug,
+)]
+#[sqlx(type_name = "promo_view_kind")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum DBPromoViewKind {
+    IMPRESSION,
+    VIEW,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct DBPromoView {
+    pub promo_id: Uuid,
+    pub user_id: Uuid,
+    pub kind: DBPromoViewKind,
+    pub day: NaiveDate,
+}
+
+impl DBPromoView {
+    pub async fn insert_many<'a, E>(views: &[Self], executor: E) -> Result<(), DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        let promo_ids: Vec<Uuid> = views.iter().map(|view| view.promo_id).collect();
+        let user_ids: Vec<Uuid> = views.iter().map(|view| view.user_id).collect();
+        let kinds: Vec<String> = views.iter().map(|view| view.kind.to_string()).collect();
+        let days: Vec<NaiveDate> = views.iter().map(|view| view.day).collect();
+
+        query_file!(
+            "sql/promo_view/insert_many.sql",
+            &promo_ids,
+            &user_ids,
+            &kinds,
+            &days
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
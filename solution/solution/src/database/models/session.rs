@@ -0,0 +1,297 @@
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    database::redis::RedisConnection,
+    models::{RefreshToken, Session, TokenType},
+};
+
+use super::{DBToken, DatabaseError};
+
+const SESSIONS_NAMESPACE: &str = "sessions";
+const ENTITY_SESSIONS_NAMESPACE: &str = "entity_sessions";
+const SESSION_LAST_SEEN_NAMESPACE: &str = "session_last_seen";
+const SESSION_TOUCHED_NAMESPACE: &str = "session_touched";
+const LAST_SEEN_PRECISION: i64 = 60;
+// Older refresh tokens are rejected without revoking the session.
+const MAX_PREVIOUS_REFRESH_HASHES: usize = 32;
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DBSession {
+    pub id: Uuid,
+
+    pub entity: Uuid,
+
+    pub token_type: TokenType,
+
+    pub token_id: Uuid,
+
+    pub refresh_generation: u32,
+
+    pub refresh_hash: String,
+
+    /// Hashes of the refresh tokens this session already rotated away from,
+    /// oldest first, so presenting one of them can be told apart from a
+    /// forged token.
+    #[serde(default)]
+    pub previous_refresh_hashes: Vec<String>,
+
+    pub issued_at: DateTime<Utc>,
+
+    pub last_seen: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+}
+
+impl DBSession {
+    pub async fn insert(self, cache: &mut RedisConnection) -> Result<Self, DatabaseError> {
+        let entity = self.entity.to_string();
+        let expiry = RefreshToken::lifetime().num_seconds();
+
+        cache
+            .set_serialized_to_json(SESSIONS_NAMESPACE, self.id, self.clone(), Some(expiry))
+            .await?;
+        cache
+            .set_serialized_to_json(
+                SESSION_LAST_SEEN_NAMESPACE,
+                self.id,
+                self.last_seen,
+                Some(expiry),
+            )
+            .await?;
+        cache
+            .add_to_set(ENTITY_SESSIONS_NAMESPACE, &entity, &self.id.to_string())
+            .await?;
+        cache
+            .expire(ENTITY_SESSIONS_NAMESPACE, &entity, expiry)
+            .await?;
+        Ok(self)
+    }
+
+    pub async fn get(id: Uuid, cache: &mut RedisConnection) -> Result<Option<Self>, DatabaseError> {
+        let session: Option<Self> = cache
+            .get_deserialized_from_json(SESSIONS_NAMESPACE, &id.to_string())
+            .await?;
+        let last_seen = cache
+            .get_deserialized_from_json(SESSION_LAST_SEEN_NAMESPACE, &id.to_string())
+            .await?;
+
+        Ok(session.map(|session| session.seen_at(last_seen)))
+    }
+
+    /// Loads the session along with its stored form, which `replace` expects.
+    pub async fn get_for_update(
+        id: Uuid,
+        cache: &mut RedisConnection,
+    ) -> Result<Option<(Self, String)>, DatabaseError> {
+        let Some(stored) = cache.get(SESSIONS_NAMESPACE, &id.to_string()).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some((serde_json::from_str(&stored)?, stored)))
+    }
+
+    /// Stores the session only if nobody changed it since it was read as
+    /// `previous`. The expiry set on sign-in is kept, so sessions can't be
+    /// extended past `REFRESH_TOKEN_LIFETIME` by refreshing them.
+    pub async fn replace(
+        &self,
+        previous: &str,
+        cache: &mut RedisConnection,
+    ) -> Result<bool, DatabaseError> {
+        cache
+            .compare_and_set(
+                SESSIONS_NAMESPACE,
+                &self.id.to_string(),
+                previous,
+                &serde_json::to_string(self)?,
+            )
+            .await
+    }
+
+    pub async fn get_all(
+        entity: Uuid,
+        cache: &mut RedisConnection,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        let entity = entity.to_string();
+
+        let ids = cache
+            .get_set_members(ENTITY_SESSIONS_NAMESPACE, &entity)
+            .await?;
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let sessions: Vec<Option<Self>> = cache
+            .get_many_deserialized_from_json(SESSIONS_NAMESPACE, &ids)
+            .await?;
+        let last_seen: Vec<Option<DateTime<Utc>>> = cache
+            .get_many_deserialized_from_json(SESSION_LAST_SEEN_NAMESPACE, &ids)
+            .await?;
+
+        let expired: Vec<String> = ids
+            .into_iter()
+            .zip(sessions.iter())
+            .filter(|(_, session)| session.is_none())
+            .map(|(id, _)| id)
+            .collect();
+        cache
+            .remove_from_set(ENTITY_SESSIONS_NAMESPACE, &entity, &expired)
+            .await?;
+
+        let mut sessions: Vec<Self> = sessions
+            .into_iter()
+            .zip(last_seen)
+            .filter_map(|(session, last_seen)| Some(session?.seen_at(last_seen)))
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.last_seen));
+
+        Ok(sessions)
+    }
+
+    /// Records that the session was used. Writes are throttled to once per
+    /// `LAST_SEEN_PRECISION` seconds and never outlive the session itself, so
+    /// most calls cost a single `SET NX`.
+    pub async fn touch(id: Uuid, cache: &mut RedisConnection) -> Result<(), DatabaseError> {
+        let due = cache
+            .set_if_absent(
+                SESSION_TOUCHED_NAMESPACE,
+                &id.to_string(),
+                "true",
+                Some(LAST_SEEN_PRECISION),
+            )
+            .await?;
+
+        if due {
+            cache
+                .update_serialized_to_json(SESSION_LAST_SEEN_NAMESPACE, id, Utc::now())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the refresh token, remembering the hash of the current one.
+    pub fn rotate_refresh(&mut self, refresh_token: &RefreshToken) {
+        let previous = std::mem::replace(&mut self.refresh_hash, refresh_token.hash());
+        self.previous_refresh_hashes.push(previous);
+        if self.previous_refresh_hashes.len() > MAX_PREVIOUS_REFRESH_HASHES {
+            self.previous_refresh_hashes.remove(0);
+        }
+        self.refresh_generation = refresh_token.generation;
+    }
+
+    /// Whether `refresh_token` was issued for this session before the current
+    /// one and has been rotated away from since.
+    pub fn is_previous_refresh(&self, refresh_token: &RefreshToken) -> bool {
+        let age = self
+            .refresh_generation
+            .saturating_sub(refresh_token.generation) as usize;
+
+        age > 0
+            && self
+                .previous_refresh_hashes
+                .iter()
+                .rev()
+                .nth(age - 1)
+                .is_some_and(|hash| *hash == refresh_token.hash())
+    }
+
+    fn seen_at(mut self, last_seen: Option<DateTime<Utc>>) -> Self {
+        self.last_seen = self.last_seen.max(last_seen.unwrap_or(self.last_seen));
+        self
+    }
+
+    pub async fn delete(self, cache: &mut RedisConnection) -> Result<(), DatabaseError> {
+        DBToken::revoke(self.token_id, cache).await?;
+        cache
+            .delete_many([
+                (SESSIONS_NAMESPACE, Some(self.id.to_string())),
+                (SESSION_LAST_SEEN_NAMESPACE, Some(self.id.to_string())),
+                (SESSION_TOUCHED_NAMESPACE, Some(self.id.to_string())),
+            ])
+            .await?;
+        cache
+            .remove_from_set(
+                ENTITY_SESSIONS_NAMESPACE,
+                &self.entity.to_string(),
+                &[self.id.to_string()],
+            )
+            .await
+    }
+
+    pub async fn delete_all(
+        entity: Uuid,
+        cache: &mut RedisConnection,
+    ) -> Result<(), DatabaseError> {
+        for session in Self::get_all(entity, cache).await? {
+            session.delete(cache).await?;
+        }
+
+        cache.delete(ENTITY_SESSIONS_NAMESPACE, entity).await
+    }
+
+    pub fn into_model(self) -> Session {
+        Session::from(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(refresh_token: &RefreshToken) -> DBSession {
+        DBSession {
+            id: refresh_token.session_id,
+            entity: Uuid::nil(),
+            token_type: TokenType::User,
+            token_id: Uuid::nil(),
+            refresh_generation: refresh_token.generation,
+            refresh_hash: refresh_token.hash(),
+            previous_refresh_hashes: vec![],
+            issued_at: Utc::now(),
+            last_seen: Utc::now(),
+            user_agent: None,
+            ip: None,
+        }
+    }
+
+    #[test]
+    fn recognizes_rotated_refresh_tokens() {
+        let first = RefreshToken::new(Uuid::now_v7(), 0);
+        let mut session = session(&first);
+        assert!(!session.is_previous_refresh(&first));
+
+        let mut tokens = vec![first];
+        for generation in 1..=MAX_PREVIOUS_REFRESH_HASHES as u32 + 5 {
+            let token = RefreshToken::new(session.id, generation);
+            session.rotate_refresh(&token);
+            tokens.push(token);
+        }
+        let current = tokens.pop().unwrap();
+
+        assert!(!session.is_previous_refresh(&current));
+        assert_eq!(
+            session.previous_refresh_hashes.len(),
+            MAX_PREVIOUS_REFRESH_HASHES
+        );
+        for (generation, token) in tokens.iter().enumerate() {
+            let remembered = generation >= tokens.len() - MAX_PREVIOUS_REFRESH_HASHES;
+            assert_eq!(session.is_previous_refresh(token), remembered);
+        }
+
+        // Forged from a known session id and generation
+        let forged = RefreshToken {
+            secret: "anything".to_string(),
+            ..RefreshToken::new(session.id, current.generation - 1)
+        };
+        assert!(!session.is_previous_refresh(&forged));
+    }
+}
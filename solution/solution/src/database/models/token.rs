@@ -9,8 +9,7 @@ use crate::{
 
 use super::DatabaseError;
 
-const TOKENS_NAMESPACE: &str = "tokens";
-const TOKEN_EXPIRY: i64 = 1000 * 60 * 60 * 24;
+const REVOKED_TOKENS_NAMESPACE: &str = "revoked_tokens";
 
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub struct DBToken {
@@ -22,56 +21,31 @@ pub struct DBToken {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_id: Option<Uuid>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_id: Option<Uuid>,
+
+    pub session_id: Uuid,
+
     pub issued_at: DateTime<Utc>,
 }
 
 impl DBToken {
-    pub async fn insert(self, cache: &mut RedisConnection) -> Result<Self, DatabaseError> {
-        let entity = self.get_entity()?;
-
+    pub async fn revoke(id: Uuid, cache: &mut RedisConnection) -> Result<(), DatabaseError> {
         cache
-            .set_serialized_to_json(TOKENS_NAMESPACE, entity, self.clone(), Some(TOKEN_EXPIRY))
-            .await?;
-        Ok(self)
-    }
-
-    pub async fn get(entity: Uuid, cache: &mut RedisConnection) -> Option<Self> {
-        cache
-            .get_deserialized_from_json(TOKENS_NAMESPACE, &entity.to_string())
+            .set(
+                REVOKED_TOKENS_NAMESPACE,
+                &id.to_string(),
+                "true",
+                Some(Token::lifetime().num_seconds()),
+            )
             .await
-            .unwrap_or(None)
-    }
-
-    pub async fn validate(self, cache: &mut RedisConnection) -> Result<Self, ()> {
-        let entity = if let Ok(entity) = self.get_entity() {
-            entity
-        } else {
-            return Err(());
-        };
-
-        if let Some(token) = Self::get(entity, cache).await {
-            if token == self {
-                return Ok(token);
-            }
-        }
-        Err(())
     }
 
-    pub fn get_entity(&self) -> Result<Uuid, DatabaseError> {
-        Ok(*match self {
-            Self {
-                company_id: Some(id),
-                ..
-            } => id,
-            Self {
-                user_id: Some(id), ..
-            } => id,
-            _ => {
-                return Err(DatabaseError::CustomCacheError(
-                    "failed to cache token".to_string(),
-                ))
-            }
-        })
+    pub async fn is_revoked(id: Uuid, cache: &mut RedisConnection) -> Result<bool, DatabaseError> {
+        Ok(cache
+            .get(REVOKED_TOKENS_NAMESPACE, &id.to_string())
+            .await?
+            .is_some())
     }
 
     pub fn into_model(self) -> Token {
@@ -86,12 +60,24 @@ impl From<Token> for DBToken {
                 id: token.id,
                 company_id: Some(token.entity),
                 user_id: None,
+                member_id: None,
+                session_id: token.session_id,
                 issued_at: token.issued_at,
             },
             TokenType::User => Self {
                 id: token.id,
                 company_id: None,
                 user_id: Some(token.entity),
+                member_id: None,
+                session_id: token.session_id,
+                issued_at: token.issued_at,
+            },
+            TokenType::Member => Self {
+                id: token.id,
+                company_id: None,
+                user_id: None,
+                member_id: Some(token.entity),
+                session_id: token.session_id,
                 issued_at: token.issued_at,
             },
         }
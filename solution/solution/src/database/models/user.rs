@@ -1,6 +1,6 @@
 use sqlx::{
     prelude::{FromRow, Type},
-    query_file_as, Executor, Postgres, Transaction,
+    query_file, query_file_as, Executor, Postgres, Transaction,
 };
 use uuid::Uuid;
 
@@ -39,6 +39,7 @@ pub struct DBUser {
     pub avatar_url: Option<String>,
     pub other: DBUserTargetSettings,
     pub password_hash: String,
+    pub interests: Vec<String>,
 }
 
 impl DBUser {
@@ -56,6 +57,7 @@ impl DBUser {
             self.avatar_url,
             self.other as DBUserTargetSettings,
             self.password_hash,
+            &self.interests,
         )
         .fetch_one(&mut **transaction)
         .await?)
@@ -87,6 +89,7 @@ impl DBUser {
         surname: Option<&str>,
         avatar_url: Option<&str>,
         password_hash: Option<&str>,
+        interests: Option<&[String]>,
         transaction: &mut Transaction<'_, Postgres>,
     ) -> Result<Self, DatabaseError> {
         Ok(query_file_as!(
@@ -96,12 +99,23 @@ impl DBUser {
             name,
             surname,
             avatar_url,
-            password_hash
+            password_hash,
+            interests
         )
         .fetch_one(&mut **transaction)
         .await?)
     }
 
+    pub async fn get_interests<'a, E>(id: Uuid, executor: E) -> Result<Vec<String>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file!("sql/user/interests.sql", id)
+            .fetch_one(executor)
+            .await?
+            .interests)
+    }
+
     pub fn into_model(self) -> User {
         User::from(self)
     }
@@ -117,6 +131,7 @@ impl From<User> for DBUser {
             avatar_url: user_model.avatar_url,
             other: user_model.other.into_db(),
             password_hash: user_model.password_hash,
+            interests: user_model.interests,
         }
     }
 }
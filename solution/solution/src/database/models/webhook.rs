@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_file, query_file_as, Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::models::Webhook;
+
+use super::DatabaseError;
+
+#[derive(Debug)]
+pub struct DBWebhook {
+    pub id: Uuid,
+    pub company_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl DBWebhook {
+    pub async fn insert(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/webhook/insert.sql",
+            self.id,
+            self.company_id,
+            self.url,
+            self.secret,
+            &self.events,
+            self.active,
+            self.created_at
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn get_by_id<'a, E>(id: Uuid, executor: E) -> Result<Option<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(Self, "sql/webhook/get_by_id.sql", id)
+            .fetch_optional(executor)
+            .await?)
+    }
+
+    pub async fn get_all<'a, E>(company_id: Uuid, executor: E) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(Self, "sql/webhook/get_all.sql", company_id)
+            .fetch_all(executor)
+            .await?)
+    }
+
+    pub async fn patch(
+        self,
+        url: Option<String>,
+        events: Option<Vec<String>>,
+        active: Option<bool>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Self, DatabaseError> {
+        Ok(query_file_as!(
+            Self,
+            "sql/webhook/patch.sql",
+            self.id,
+            url,
+            events.as_deref(),
+            active
+        )
+        .fetch_one(&mut **transaction)
+        .await?)
+    }
+
+    pub async fn delete(
+        self,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), DatabaseError> {
+        query_file!("sql/webhook/delete.sql", self.id)
+            .execute(&mut **transaction)
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn into_model(self) -> Webhook {
+        Webhook::from(self)
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{prelude::Type, query_file, query_file_as, Executor, Postgres};
+use uuid::Uuid;
+
+use crate::models::{PageRequest, WebhookDelivery};
+
+use super::DatabaseError;
+
+#[derive(Type, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[sqlx(type_name = "webhook_delivery_status")]
+pub enum DBWebhookDeliveryStatus {
+    PENDING,
+    SUCCEEDED,
+    FAILED,
+}
+
+#[derive(Debug)]
+pub struct DBWebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: Value,
+    pub status: DBWebhookDeliveryStatus,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl DBWebhookDelivery {
+    pub async fn get_pageable<'a, E>(
+        webhook_id: Uuid,
+        page: &PageRequest,
+        executor: E,
+    ) -> Result<(Vec<Self>, Option<i64>), DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres> + Copy,
+    {
+        let deliveries = query_file_as!(
+            Self,
+            "sql/webhook_delivery/get_pageable.sql",
+            webhook_id,
+            page.limit,
+            page.offset,
+            page.cursor_date(),
+            page.cursor_id()
+        )
+        .fetch_all(executor)
+        .await?;
+
+        if !page.with_total {
+            return Ok((deliveries, None));
+        }
+
+        let count = query_file!("sql/webhook_delivery/count.sql", webhook_id)
+            .fetch_one(executor)
+            .await?
+            .count;
+
+        Ok((deliveries, count))
+    }
+
+    pub async fn claim_due<'a, E>(
+        limit: i64,
+        lease_seconds: f64,
+        executor: E,
+    ) -> Result<Vec<Self>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(query_file_as!(
+            Self,
+            "sql/webhook_delivery/claim_due.sql",
+            limit,
+            lease_seconds
+        )
+        .fetch_all(executor)
+        .await?)
+    }
+
+    pub async fn update_attempt<'a, E>(&self, executor: E) -> Result<(), DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        query_file!(
+            "sql/webhook_delivery/update_attempt.sql",
+            self.id,
+            self.status as DBWebhookDeliveryStatus,
+            self.attempts,
+            self.response_status,
+            self.last_error,
+            self.next_attempt_at,
+            self.last_attempt_at
+        )
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub fn into_model(self) -> WebhookDelivery {
+        WebhookDelivery::from(self)
+    }
+}
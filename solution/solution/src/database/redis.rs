@@ -2,7 +2,7 @@ use crate::{REDIS_HOST, REDIS_PORT};
 
 use super::models::DatabaseError;
 use deadpool_redis::{Config, Connection, Pool, Runtime};
-use redis::{cmd, Cmd};
+use redis::{cmd, Cmd, Script};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -147,6 +147,202 @@ impl RedisConnection {
             .collect::<Vec<_>>())
     }
 
+    pub async fn update(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        data: &str,
+    ) -> Result<(), DatabaseError> {
+        let mut cmd = cmd("SET");
+        redis_args(
+            &mut cmd,
+            vec![
+                format!("{}_{}:{}", self.meta_namespace, namespace, id),
+                data.to_string(),
+                "XX".to_string(),
+                "KEEPTTL".to_string(),
+            ]
+            .as_slice(),
+        );
+        redis_execute::<()>(&mut cmd, &mut self.connection).await?;
+        Ok(())
+    }
+
+    pub async fn update_serialized_to_json<Id, D>(
+        &mut self,
+        namespace: &str,
+        id: Id,
+        data: D,
+    ) -> Result<(), DatabaseError>
+    where
+        Id: Display,
+        D: serde::Serialize,
+    {
+        self.update(namespace, &id.to_string(), &serde_json::to_string(&data)?)
+            .await
+    }
+
+    /// Replaces the value only if it still equals `expected`, keeping its
+    /// expiry. Returns whether the value was replaced.
+    pub async fn compare_and_set(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        expected: &str,
+        data: &str,
+    ) -> Result<bool, DatabaseError> {
+        let script = Script::new(
+            r"
+            if redis.call('GET', KEYS[1]) == ARGV[1] then
+                redis.call('SET', KEYS[1], ARGV[2], 'KEEPTTL')
+                return 1
+            end
+            return 0
+            ",
+        );
+        let res: i32 = script
+            .key(format!("{}_{}:{}", self.meta_namespace, namespace, id))
+            .arg(expected)
+            .arg(data)
+            .invoke_async(&mut self.connection)
+            .await?;
+        Ok(res == 1)
+    }
+
+    pub async fn set_if_absent(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        data: &str,
+        expiry: Option<i64>,
+    ) -> Result<bool, DatabaseError> {
+        let mut cmd = cmd("SET");
+        redis_args(
+            &mut cmd,
+            vec![
+                format!("{}_{}:{}", self.meta_namespace, namespace, id),
+                data.to_string(),
+                "NX".to_string(),
+                "EX".to_string(),
+                expiry.unwrap_or(DEFAULT_EXPIRY).to_string(),
+            ]
+            .as_slice(),
+        );
+        let res: Option<String> = redis_execute(&mut cmd, &mut self.connection).await?;
+        Ok(res.is_some())
+    }
+
+    pub async fn push_to_list(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        items: &[String],
+    ) -> Result<(), DatabaseError> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let mut cmd = cmd("RPUSH");
+        redis_args(
+            &mut cmd,
+            vec![format!("{}_{}:{}", self.meta_namespace, namespace, id)].as_slice(),
+        );
+        redis_args(&mut cmd, items);
+        redis_execute::<()>(&mut cmd, &mut self.connection).await?;
+        Ok(())
+    }
+
+    pub async fn pop_from_list(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        count: usize,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let mut cmd = cmd("LPOP");
+        redis_args(
+            &mut cmd,
+            vec![
+                format!("{}_{}:{}", self.meta_namespace, namespace, id),
+                count.to_string(),
+            ]
+            .as_slice(),
+        );
+        let res: Option<Vec<String>> = redis_execute(&mut cmd, &mut self.connection).await?;
+        Ok(res.unwrap_or_default())
+    }
+
+    pub async fn add_to_set(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        member: &str,
+    ) -> Result<(), DatabaseError> {
+        let mut cmd = cmd("SADD");
+        redis_args(
+            &mut cmd,
+            vec![
+                format!("{}_{}:{}", self.meta_namespace, namespace, id),
+                member.to_string(),
+            ]
+            .as_slice(),
+        );
+        redis_execute::<()>(&mut cmd, &mut self.connection).await?;
+        Ok(())
+    }
+
+    pub async fn remove_from_set(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        members: &[String],
+    ) -> Result<(), DatabaseError> {
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        let mut cmd = cmd("SREM");
+        redis_args(
+            &mut cmd,
+            vec![format!("{}_{}:{}", self.meta_namespace, namespace, id)].as_slice(),
+        );
+        redis_args(&mut cmd, members);
+        redis_execute::<()>(&mut cmd, &mut self.connection).await?;
+        Ok(())
+    }
+
+    pub async fn expire(
+        &mut self,
+        namespace: &str,
+        id: &str,
+        expiry: i64,
+    ) -> Result<(), DatabaseError> {
+        let mut cmd = cmd("EXPIRE");
+        redis_args(
+            &mut cmd,
+            vec![
+                format!("{}_{}:{}", self.meta_namespace, namespace, id),
+                expiry.to_string(),
+            ]
+            .as_slice(),
+        );
+        redis_execute::<()>(&mut cmd, &mut self.connection).await?;
+        Ok(())
+    }
+
+    pub async fn get_set_members(
+        &mut self,
+        namespace: &str,
+        id: &str,
+    ) -> Result<Vec<String>, DatabaseError> {
+        let mut cmd = cmd("SMEMBERS");
+        redis_args(
+            &mut cmd,
+            vec![format!("{}_{}:{}", self.meta_namespace, namespace, id)].as_slice(),
+        );
+        let res = redis_execute(&mut cmd, &mut self.connection).await?;
+        Ok(res)
+    }
+
     pub async fn delete<T1>(&mut self, namespace: &str, id: T1) -> Result<(), DatabaseError>
     where
         T1: Display,
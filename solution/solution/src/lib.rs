@@ -1,13 +1,18 @@
 use actix_web::web::{get, Data, JsonConfig, PathConfig, ServiceConfig};
-use database::redis::RedisPool;
+use database::{models::DBEvent, redis::RedisPool};
 use log::{info, warn};
-use scheduler::{update_promos_active, Scheduler};
+use scheduler::{watch_promo_transitions, Scheduler};
 use sqlx::{Pool, Postgres};
 use std::sync::OnceLock;
 use std::time::Duration;
 use std::{env, sync::Arc};
 
-use crate::routes::{not_found, ApiError};
+use crate::{
+    auth::SigningKeys,
+    models::{RefreshToken, Token},
+    routes::{not_found, ApiError},
+    util::{views, webhooks},
+};
 
 pub mod auth;
 pub mod database;
@@ -22,6 +27,13 @@ environment_variables! {
     REDIS_HOST: "localhost",
     REDIS_PORT: "6379",
     ANTIFRAUD_ADDRESS: "localhost:9090",
+    ACCESS_TOKEN_LIFETIME: "3600",
+    REFRESH_TOKEN_LIFETIME: "2592000",
+    WEBHOOK_DELIVERY_INTERVAL: "5",
+    WEBHOOK_MAX_ATTEMPTS: "8",
+    EVENTS_RETENTION: "604800",
+    VIEWS_FLUSH_INTERVAL: "10",
+    IDEMPOTENCY_KEY_TTL: "86400",
 }
 
 #[derive(Clone)]
@@ -33,19 +45,63 @@ pub struct SolutionConfig {
 
 pub fn app_setup(pool: Pool<Postgres>, redis_pool: RedisPool) -> SolutionConfig {
     info!("Starting Solution on {}", SERVER_ADDRESS());
+    info!(
+        "Access tokens live for {}s, refresh tokens for {}s",
+        Token::lifetime().num_seconds(),
+        RefreshToken::lifetime().num_seconds()
+    );
+    info!(
+        "Signing tokens with key `{}`",
+        SigningKeys::get().current().id
+    );
 
     let mut scheduler = Scheduler::new();
 
+    scheduler.spawn(watch_promo_transitions(pool.clone()));
+    scheduler.spawn(webhooks::watch_events(pool.clone()));
+
     let pool_ref = pool.clone();
-    scheduler.run(Duration::from_secs(60 * 60 * 24), move || {
+    let events_retention = chrono::Duration::seconds(
+        EVENTS_RETENTION()
+            .parse()
+            .expect("`EVENTS_RETENTION` must be a number of seconds"),
+    );
+    scheduler.run(Duration::from_secs(60 * 60), move || {
         let pool_ref = pool_ref.clone();
         async move {
-            info!("Updating `active` field on promos");
-            let result = update_promos_active(&pool_ref).await;
+            let result =
+                DBEvent::delete_older_than(chrono::Utc::now() - events_retention, &pool_ref).await;
             if let Err(e) = result {
-                warn!("Updating `active` field failed: {:?}", e);
+                warn!("Pruning events failed: {:?}", e);
+            }
+        }
+    });
+
+    let pool_ref = pool.clone();
+    let webhook_interval = WEBHOOK_DELIVERY_INTERVAL()
+        .parse()
+        .expect("`WEBHOOK_DELIVERY_INTERVAL` must be a number of seconds");
+    scheduler.run(Duration::from_secs(webhook_interval), move || {
+        let pool_ref = pool_ref.clone();
+        async move {
+            if let Err(e) = webhooks::deliver_pending(&pool_ref).await {
+                warn!("Delivering webhooks failed: {:?}", e);
+            }
+        }
+    });
+
+    let pool_ref = pool.clone();
+    let redis_pool_ref = redis_pool.clone();
+    let views_interval = VIEWS_FLUSH_INTERVAL()
+        .parse()
+        .expect("`VIEWS_FLUSH_INTERVAL` must be a number of seconds");
+    scheduler.run(Duration::from_secs(views_interval), move || {
+        let pool_ref = pool_ref.clone();
+        let redis_pool_ref = redis_pool_ref.clone();
+        async move {
+            if let Err(e) = views::flush(&redis_pool_ref, &pool_ref).await {
+                warn!("Flushing promo views failed: {:?}", e);
             }
-            info!("Done updating `active` field on promos");
         }
     });
 
@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString, IntoStaticStr};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::models::DBApiKey;
+
+use super::CompanyPermission;
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct ApiKeyPath {
+    pub api_key_id: Uuid,
+}
+
+#[derive(
+    Deserialize, Serialize, Clone, Copy, Display, PartialEq, Eq, EnumString, IntoStaticStr, Debug,
+)]
+pub enum ApiKeyScope {
+    #[serde(rename = "promo:read")]
+    #[strum(serialize = "promo:read")]
+    PromoRead,
+
+    #[serde(rename = "promo:write")]
+    #[strum(serialize = "promo:write")]
+    PromoWrite,
+
+    #[serde(rename = "stats:read")]
+    #[strum(serialize = "stats:read")]
+    StatsRead,
+}
+
+impl ApiKeyScope {
+    pub fn permission(&self) -> CompanyPermission {
+        match self {
+            Self::PromoRead => CompanyPermission::PromoRead,
+            Self::PromoWrite => CompanyPermission::PromoWrite,
+            Self::StatsRead => CompanyPermission::StatsRead,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ApiKey {
+    pub id: Uuid,
+
+    #[serde(skip)]
+    pub company_id: Uuid,
+
+    pub name: String,
+
+    pub scopes: Vec<ApiKeyScope>,
+
+    pub created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<DBApiKey> for ApiKey {
+    fn from(db_api_key: DBApiKey) -> Self {
+        Self {
+            id: db_api_key.id,
+            company_id: db_api_key.company_id,
+            name: db_api_key.name,
+            scopes: db_api_key
+                .scopes
+                .iter()
+                .filter_map(|scope| scope.parse().ok())
+                .collect(),
+            created_at: db_api_key.created_at,
+            expires_at: db_api_key.expires_at,
+        }
+    }
+}
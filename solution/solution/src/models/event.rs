@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::database::models::DBEvent;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Event {
+    pub id: i64,
+    #[serde(skip)]
+    pub transaction_id: i64,
+    pub event: String,
+    pub company_id: Uuid,
+    pub promo_id: Uuid,
+    pub payload: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<DBEvent> for Event {
+    fn from(db_event: DBEvent) -> Self {
+        Self {
+            id: db_event.id,
+            transaction_id: db_event.transaction_id,
+            event: db_event.event,
+            company_id: db_event.company_id,
+            promo_id: db_event.promo_id,
+            payload: db_event.payload,
+            created_at: db_event.created_at,
+        }
+    }
+}
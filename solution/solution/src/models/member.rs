@@ -0,0 +1,175 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Executor, Postgres};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::AuthenticationError,
+    database::models::{
+        DBApiKey, DBCompany, DBCompanyMember, DBCompanyRole, DBRevisionAuthor, DatabaseError,
+    },
+    routes::ApiError,
+};
+
+use super::{ApiKeyScope, ApiKeyToken, Company, Token, TokenType};
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct MemberPath {
+    pub member_id: Uuid,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CompanyPermission {
+    PromoRead,
+    PromoWrite,
+    StatsRead,
+    MembersManage,
+    ApiKeysManage,
+    WebhooksManage,
+}
+
+impl CompanyPermission {
+    pub fn granted_to(role: DBCompanyRole) -> &'static [Self] {
+        match role {
+            DBCompanyRole::OWNER => &[
+                Self::PromoRead,
+                Self::PromoWrite,
+                Self::StatsRead,
+                Self::MembersManage,
+                Self::ApiKeysManage,
+                Self::WebhooksManage,
+            ],
+            DBCompanyRole::MANAGER => &[Self::PromoRead, Self::PromoWrite, Self::StatsRead],
+            DBCompanyRole::ANALYST => &[Self::PromoRead, Self::StatsRead],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CompanyActor {
+    pub company_id: Uuid,
+    pub member_id: Option<Uuid>,
+    pub role: DBCompanyRole,
+    pub scopes: Option<Vec<CompanyPermission>>,
+}
+
+impl CompanyActor {
+    pub async fn resolve<'a, E>(token: &Token, executor: E) -> Result<Self, AuthenticationError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        match token.token_type {
+            TokenType::Company => Ok(Self {
+                company_id: token.entity,
+                member_id: None,
+                role: DBCompanyRole::OWNER,
+                scopes: None,
+            }),
+            TokenType::Member => match DBCompanyMember::get_by_id(token.entity, executor).await? {
+                Some(member) => Ok(Self {
+                    company_id: member.company_id,
+                    member_id: Some(member.id),
+                    role: member.role,
+                    scopes: None,
+                }),
+                None => Err(AuthenticationError::InvalidCredentials),
+            },
+            TokenType::User => Err(AuthenticationError::IcorrectTokenType),
+        }
+    }
+
+    pub async fn resolve_api_key<'a, E>(
+        api_key: &ApiKeyToken,
+        executor: E,
+    ) -> Result<Self, AuthenticationError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        let db_api_key = match DBApiKey::get_by_id(api_key.key_id, executor).await? {
+            Some(db_api_key) if db_api_key.key_hash == api_key.hash() => db_api_key,
+            _ => return Err(AuthenticationError::InvalidCredentials),
+        };
+
+        if db_api_key.is_expired() {
+            return Err(AuthenticationError::ExpiredToken);
+        }
+
+        let api_key = db_api_key.into_model();
+
+        Ok(Self {
+            company_id: api_key.company_id,
+            member_id: None,
+            role: DBCompanyRole::OWNER,
+            scopes: Some(api_key.scopes.iter().map(ApiKeyScope::permission).collect()),
+        })
+    }
+
+    pub fn can(&self, permission: CompanyPermission) -> bool {
+        CompanyPermission::granted_to(self.role).contains(&permission)
+            && self
+                .scopes
+                .as_ref()
+                .is_none_or(|scopes| scopes.contains(&permission))
+    }
+
+    pub fn revision_author(&self) -> DBRevisionAuthor {
+        match (self.member_id, &self.scopes) {
+            (Some(_), _) => DBRevisionAuthor::MEMBER,
+            (None, Some(_)) => DBRevisionAuthor::INTEGRATION,
+            (None, None) => DBRevisionAuthor::COMPANY,
+        }
+    }
+
+    pub fn require(&self, permission: CompanyPermission) -> Result<(), ApiError> {
+        if self.can(permission) {
+            Ok(())
+        } else {
+            Err(ApiError::InsufficientPermissions)
+        }
+    }
+
+    pub async fn get_company<'a, E>(&self, executor: E) -> Result<Company, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        Ok(DBCompany::get_by_id(self.company_id, executor)
+            .await?
+            .unwrap()
+            .into_model())
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompanyMember {
+    pub id: Uuid,
+
+    #[serde(skip)]
+    pub company_id: Uuid,
+
+    pub email: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    pub role: DBCompanyRole,
+
+    pub invited_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<DateTime<Utc>>,
+}
+
+impl From<DBCompanyMember> for CompanyMember {
+    fn from(db_member: DBCompanyMember) -> Self {
+        Self {
+            id: db_member.id,
+            company_id: db_member.company_id,
+            email: db_member.email,
+            name: db_member.name,
+            role: db_member.role,
+            invited_at: db_member.invited_at,
+            joined_at: db_member.joined_at,
+        }
+    }
+}
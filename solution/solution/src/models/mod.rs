@@ -2,20 +2,37 @@ use actix_web::{body::EitherBody, web::Json, HttpRequest, HttpResponse, Responde
 use serde::{Deserialize, Serialize};
 
 mod antifraud;
+mod api_key;
 mod comment;
 mod company;
+mod event;
+mod member;
+mod page;
 mod promo;
+mod promo_revision;
+mod session;
 mod stats;
 mod token;
 mod user;
+mod webhook;
 
 pub use antifraud::{AntiFraudRequest, AntiFraudResponse};
+pub use api_key::{ApiKey, ApiKeyPath, ApiKeyScope};
 pub use comment::{Comment, CommentPath};
 pub use company::Company;
-pub use promo::{Promo, PromoPath, PromoTarget, SortPromosBy, UserPromo};
-pub use stats::{PromoStats, PromoStatsCountry};
-pub use token::{Token, TokenType};
+pub use event::Event;
+pub use member::{CompanyActor, CompanyMember, CompanyPermission, MemberPath};
+pub use page::{Cursor, Page, PageRequest};
+pub use promo::{Promo, PromoPatch, PromoPath, PromoTarget, SortFeedBy, SortPromosBy, UserPromo};
+pub use promo_revision::{PromoRevision, PromoSnapshot};
+pub use session::{ClientInfo, Session, SessionPath};
+pub use stats::{
+    CompanyPromoStats, CompanyStats, PromoStats, PromoStatsCountry, PromoTimeseries, StatsBucket,
+    StatsFunnel, StatsSplit,
+};
+pub use token::{ApiKeyToken, InviteToken, RefreshToken, Token, TokenType};
 pub use user::{User, UserTargetSettings};
+pub use webhook::{Webhook, WebhookDelivery, WebhookEvent, WebhookPath};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiError<'a> {
@@ -0,0 +1,159 @@
+use actix_web::{HttpRequest, HttpResponse};
+use base64::{
+    alphabet::URL_SAFE,
+    engine::{general_purpose::NO_PAD, GeneralPurpose},
+    Engine,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::routes::ApiError;
+
+const MAX_PAGE_SIZE: u32 = 57;
+const DEFAULT_PAGE_SIZE: u32 = 10;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+pub struct Cursor {
+    #[serde(rename = "d", skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateTime<Utc>>,
+
+    #[serde(rename = "i")]
+    pub id: Uuid,
+}
+
+impl Cursor {
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(&URL_SAFE, NO_PAD);
+
+    pub fn new(id: Uuid) -> Self {
+        Self { date: None, id }
+    }
+
+    pub fn with_date(date: DateTime<Utc>, id: Uuid) -> Self {
+        Self {
+            date: Some(date),
+            id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        Self::ENGINE.encode(serde_json::to_vec(self).unwrap())
+    }
+
+    pub fn decode(value: &str) -> Result<Self, ApiError> {
+        Self::ENGINE
+            .decode(value)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| ApiError::InvalidInput("Malformed cursor".to_string()))
+    }
+
+    pub fn require_date(&self) -> Result<DateTime<Utc>, ApiError> {
+        self.date.ok_or_else(|| {
+            ApiError::InvalidInput("Cursor doesn't match the requested sorting".to_string())
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PageRequest {
+    pub limit: i64,
+    pub offset: i64,
+    pub cursor: Option<Cursor>,
+    pub with_total: bool,
+}
+
+impl PageRequest {
+    pub fn new(
+        limit: Option<u32>,
+        offset: Option<u32>,
+        cursor: Option<&str>,
+        total: Option<bool>,
+    ) -> Result<Self, ApiError> {
+        let cursor = cursor.map(Cursor::decode).transpose()?;
+        if cursor.is_some() && offset.is_some_and(|offset| offset > 0) {
+            return Err(ApiError::InvalidInput(
+                "Cursor and offset can't be used together".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            limit: limit.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE).into(),
+            offset: offset.unwrap_or(0).into(),
+            cursor,
+            with_total: total.unwrap_or(cursor.is_none()),
+        })
+    }
+
+    pub fn cursor_id(&self) -> Option<Uuid> {
+        self.cursor.map(|cursor| cursor.id)
+    }
+
+    pub fn cursor_date(&self) -> Option<DateTime<Utc>> {
+        self.cursor.and_then(|cursor| cursor.date)
+    }
+}
+
+#[derive(Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: Option<i64>,
+    pub next_cursor: Option<Cursor>,
+}
+
+impl<T> Page<T> {
+    pub fn new<F>(items: Vec<T>, total: Option<i64>, request: &PageRequest, key: F) -> Self
+    where
+        F: Fn(&T) -> Cursor,
+    {
+        let next_cursor = if items.len() as i64 == request.limit {
+            items.last().map(key)
+        } else {
+            None
+        };
+
+        Self {
+            items,
+            total,
+            next_cursor,
+        }
+    }
+
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+impl<T: Serialize> Page<T> {
+    pub fn into_response(self, req: &HttpRequest) -> HttpResponse {
+        let mut response = HttpResponse::Ok();
+
+        if let Some(total) = self.total {
+            response.insert_header(("X-Total-Count", total));
+        }
+
+        if let Some(cursor) = self.next_cursor {
+            let query = req
+                .query_string()
+                .split('&')
+                .filter(|pair| {
+                    let key = pair.split('=').next().unwrap_or_default();
+                    !pair.is_empty() && key != "cursor" && key != "offset"
+                })
+                .chain([format!("cursor={}", cursor.encode()).as_str()])
+                .collect::<Vec<_>>()
+                .join("&");
+
+            response.insert_header(("Link", format!("<{}?{}>; rel=\"next\"", req.path(), query)));
+        }
+
+        response.json(self.items)
+    }
+}
@@ -1,24 +1,33 @@
 use crate::{
     database::{
-        models::{DBLike, DBPromo, DBPromoActivation, DBPromoMode, DBTarget, DatabaseError},
+        models::{
+            DBActivationUsage, DBLike, DBPromo, DBPromoActivation, DBPromoMode, DBPromoQuery,
+            DBPromoSortField, DBPromoStatus, DBSortOrder, DBTarget,
+        },
         redis::RedisPool,
     },
     routes::ApiError,
     util::{
         antifraud,
-        convertions::serialize_opt_promo_date,
+        convertions::{promo_date_format, serialize_opt_promo_date},
+        ranking,
         validate::{validate_country, validate_target},
         values::{MAX_DATETIME, MIN_DATETIME},
     },
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use futures::{stream::FuturesOrdered, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use sqlx::{Executor, PgPool, Postgres};
+use sqlx::{Executor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 use validator::Validate;
 
-use super::User;
+use super::{Page, PageRequest, User};
+
+/// Relevance ranking only reorders this many of the newest matching promos.
+/// Older ones follow them in recency order, freshness makes them rank low
+/// anyway.
+const RANKING_CANDIDATES_LIMIT: i64 = 1000;
 
 #[derive(Deserialize, Validate, Debug)]
 pub struct PromoPath {
@@ -32,6 +41,23 @@ pub enum SortPromosBy {
     ActiveUntil,
 }
 
+impl SortPromosBy {
+    pub fn into_db(self) -> DBPromoSortField {
+        match self {
+            Self::ActiveFrom => DBPromoSortField::ActiveFrom,
+            Self::ActiveUntil => DBPromoSortField::ActiveUntil,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SortFeedBy {
+    #[default]
+    Recent,
+    Relevance,
+}
+
 #[derive(Deserialize, Serialize, Validate, Clone, Debug)]
 #[validate(schema(function = "validate_target"))]
 pub struct PromoTarget {
@@ -69,6 +95,37 @@ impl From<DBTarget> for PromoTarget {
     }
 }
 
+/// Fields to change on a promo, omitted ones keep their current value.
+#[derive(Deserialize, Validate, Debug)]
+pub struct PromoPatch {
+    #[validate(length(min = 10, max = 300))]
+    pub description: Option<String>,
+
+    #[validate(length(max = 350))]
+    pub image_url: Option<String>,
+
+    #[validate(nested)]
+    pub target: Option<PromoTarget>,
+
+    #[validate(range(min = 0, max = 100000000))]
+    pub max_count: Option<i32>,
+
+    #[serde(default, with = "promo_date_format")]
+    pub active_from: Option<DateTime<Utc>>,
+
+    #[serde(default, with = "promo_date_format")]
+    pub active_until: Option<DateTime<Utc>>,
+
+    #[validate(range(min = 1, max = 100000000))]
+    pub per_user_limit: Option<i32>,
+
+    #[validate(range(min = 1, max = 31536000))]
+    pub cooldown: Option<i32>,
+
+    #[validate(range(min = 1, max = 100000000))]
+    pub max_per_day: Option<i32>,
+}
+
 #[derive(Serialize, Debug)]
 pub struct Promo {
     #[serde(rename = "promo_id")]
@@ -101,7 +158,10 @@ pub struct Promo {
     pub promo_common: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub promo_unique: Option<Vec<String>>,
+    pub promo_unique_count: Option<i64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promo_unique_available: Option<i64>,
 
     pub company_id: Uuid,
 
@@ -114,40 +174,95 @@ pub struct Promo {
     pub comment_count: i32,
 
     pub active: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_user_limit: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cooldown: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_day: Option<i32>,
+
+    pub status: DBPromoStatus,
 }
 
 impl Promo {
     pub async fn get_pageable<'a, E>(
-        company_id: Uuid,
-        limit: Option<u32>,
-        offset: Option<u32>,
-        sort_by: &Option<SortPromosBy>,
-        countries: &Option<Vec<String>>,
+        query: &DBPromoQuery,
+        page: &PageRequest,
         executor: E,
-    ) -> Result<(Vec<Self>, i64), DatabaseError>
+    ) -> Result<Page<Self>, ApiError>
     where
         E: Executor<'a, Database = Postgres> + Copy,
     {
-        let limit: i64 = match limit {
-            Some(limit) if limit > 57 => 57,
-            Some(limit) => limit.into(),
-            None => 10,
-        };
+        if let Some(cursor) = page.cursor {
+            if query.sort_by() != DBPromoSortField::Id {
+                cursor.require_date()?;
+            }
+        }
+
+        Ok(query
+            .fetch_page(page, executor)
+            .await?
+            .map(DBPromo::into_model))
+    }
+
+    /// UNIQUE promos hand out one code per user, so activating one again
+    /// returns the code that was already issued.
+    pub async fn get_issued_code<'a, E>(
+        &self,
+        user_id: Uuid,
+        executor: E,
+    ) -> Result<Option<String>, ApiError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        if self.mode != DBPromoMode::UNIQUE {
+            return Ok(None);
+        }
 
-        DBPromo::get_pageable(
-            company_id,
-            limit,
-            offset.unwrap_or(0).into(),
-            &sort_by
-                .map(|v| serde_json::to_string(&v).unwrap())
-                .unwrap_or("id".to_string()),
-            &countries
-                .clone()
-                .map(|v| v.into_iter().map(|s| s.to_lowercase()).collect()),
-            executor,
-        )
-        .await
-        .map(|v| (v.0.into_iter().map(DBPromo::into_model).collect(), v.1))
+        Ok(DBPromoActivation::get_by_ids(user_id, self.id, executor)
+            .await?
+            .map(|activation| activation.promo))
+    }
+
+    async fn check_activation_limits(
+        &self,
+        user_id: Uuid,
+        date: DateTime<Utc>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<(), ApiError> {
+        if self.per_user_limit.is_none() && self.cooldown.is_none() && self.max_per_day.is_none() {
+            return Ok(());
+        }
+
+        let usage = DBActivationUsage::get_locked(user_id, self.id, date, transaction)
+            .await?
+            .ok_or(ApiError::NotFound)?;
+
+        if let Some(limit) = self.per_user_limit {
+            if usage.user_activations >= limit.into() {
+                return Err(ApiError::ActivationLimitReached);
+            }
+        }
+
+        if let (Some(cooldown), Some(last_activation)) = (self.cooldown, usage.last_activation) {
+            let remaining = last_activation + Duration::seconds(cooldown.into()) - date;
+            if remaining > Duration::zero() {
+                return Err(ApiError::ActivationCooldown(
+                    (remaining.num_milliseconds() + 999) / 1000,
+                ));
+            }
+        }
+
+        if let Some(max_per_day) = self.max_per_day {
+            if usage.today_activations >= max_per_day.into() {
+                return Err(ApiError::DailyLimitReached);
+            }
+        }
+
+        Ok(())
     }
 
     pub async fn get_code(
@@ -166,12 +281,16 @@ impl Promo {
 
         let promo = match self.mode {
             DBPromoMode::COMMON => {
-                DBPromoActivation::activate_common(user.id, self.id, date, &mut transaction).await
+                self.check_activation_limits(user.id, date, &mut transaction)
+                    .await?;
+                DBPromoActivation::activate_common(user.id, self.id, date, &mut transaction).await?
             }
             DBPromoMode::UNIQUE => {
-                DBPromoActivation::activate_unique(user.id, self.id, date, &mut transaction).await
+                DBPromoActivation::activate_unique(user.id, self.id, date, &mut transaction)
+                    .await?
+                    .ok_or(ApiError::PromoExpired)?
             }
-        }?
+        }
         .promo;
 
         transaction.commit().await?;
@@ -198,10 +317,9 @@ impl From<DBPromo> for Promo {
             Some(db_promo.active_until)
         };
 
-        let promo_unique = if db_promo.promo_unique.is_empty() {
-            None
-        } else {
-            Some(db_promo.promo_unique)
+        let (promo_unique_count, promo_unique_available) = match db_promo.mode {
+            DBPromoMode::COMMON => (None, None),
+            DBPromoMode::UNIQUE => (Some(db_promo.unique_count), Some(db_promo.unique_available)),
         };
 
         Self {
@@ -214,13 +332,18 @@ impl From<DBPromo> for Promo {
             active_until,
             mode: db_promo.mode,
             promo_common: db_promo.promo_common,
-            promo_unique,
+            promo_unique_count,
+            promo_unique_available,
             company_id: db_promo.company_id,
             company_name: db_promo.company_name.unwrap_or_default(),
             like_count: db_promo.like_count,
             used_count: db_promo.used_count,
             comment_count: db_promo.comment_count,
             active: db_promo.active,
+            per_user_limit: db_promo.per_user_limit,
+            cooldown: db_promo.cooldown,
+            max_per_day: db_promo.max_per_day,
+            status: db_promo.status,
         }
     }
 }
@@ -230,7 +353,7 @@ pub struct UserPromo {
     #[serde(skip)]
     user_id: Uuid,
 
-    promo_id: Uuid,
+    pub promo_id: Uuid,
 
     company_id: Uuid,
 
@@ -255,30 +378,100 @@ pub struct UserPromo {
 impl UserPromo {
     pub async fn get_pageable<'a, E>(
         user: &User,
-        limit: Option<u32>,
-        offset: Option<u32>,
-        category: Option<&str>,
-        active: Option<bool>,
+        query: &DBPromoQuery,
+        page: &PageRequest,
+        sort_by: SortFeedBy,
         executor: E,
-    ) -> Result<(Vec<Self>, i64), ApiError>
+    ) -> Result<Page<Self>, ApiError>
     where
         E: Executor<'a, Database = Postgres> + Copy,
     {
-        let limit: i64 = match limit {
-            Some(limit) if limit > 57 => 57,
-            Some(limit) => limit.into(),
-            None => 10,
+        let page = match sort_by {
+            SortFeedBy::Recent => query
+                .fetch_page(page, executor)
+                .await?
+                .map(DBPromo::into_model),
+            SortFeedBy::Relevance => {
+                if page.cursor.is_some() {
+                    return Err(ApiError::InvalidInput(
+                        "Cursor pagination isn't supported for relevance sorting".to_string(),
+                    ));
+                }
+
+                let query = query.clone().sort(DBPromoSortField::Id, DBSortOrder::Desc);
+
+                let interests = user.get_interests(executor).await?;
+                let mut candidates: Vec<Promo> = query
+                    .fetch_all(RANKING_CANDIDATES_LIMIT, 0, executor)
+                    .await?
+                    .into_iter()
+                    .map(DBPromo::into_model)
+                    .collect();
+                let window = candidates.len() as i64;
+
+                ranking::rank_by_relevance(&mut candidates, &interests, Utc::now());
+
+                let mut items: Vec<Promo> = candidates
+                    .into_iter()
+                    .skip(page.offset as usize)
+                    .take(page.limit as usize)
+                    .collect();
+
+                let remaining = page.limit - items.len() as i64;
+                if window == RANKING_CANDIDATES_LIMIT && remaining > 0 {
+                    items.extend(
+                        query
+                            .fetch_all(remaining, page.offset.max(window), executor)
+                            .await?
+                            .into_iter()
+                            .map(DBPromo::into_model),
+                    );
+                }
+
+                let count = if page.with_total {
+                    Some(query.count(executor).await?)
+                } else {
+                    None
+                };
+
+                Page {
+                    items,
+                    total: count,
+                    next_cursor: None,
+                }
+            }
         };
 
-        let (db_promos, count) = DBPromo::get_pageable_user(
-            limit,
-            offset.unwrap_or(0).into(),
-            &user.other.country,
-            category,
-            active,
-            executor,
-        )
-        .await?;
+        let promos: Vec<Self> = page
+            .items
+            .into_iter()
+            .map(|promo| async move { promo.into_user(user.id, executor).await })
+            .collect::<FuturesOrdered<_>>()
+            .try_collect()
+            .await?;
+
+        Ok(Page {
+            items: promos,
+            total: page.total,
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    /// Search results are ordered by rank, so only offset pagination is
+    /// supported.
+    pub async fn search<'a, E>(
+        user: &User,
+        query: &str,
+        page: &PageRequest,
+        category: Option<&str>,
+        active: Option<bool>,
+        executor: E,
+    ) -> Result<Page<Self>, ApiError>
+    where
+        E: Executor<'a, Database = Postgres> + Copy,
+    {
+        let (db_promos, count) =
+            DBPromo::search_user(page, user.id, query, category, active, executor).await?;
 
         let promos: Vec<Self> = db_promos
             .into_iter()
@@ -290,7 +483,11 @@ impl UserPromo {
             .try_collect()
             .await?;
 
-        Ok((promos, count))
+        Ok(Page {
+            items: promos,
+            total: count,
+            next_cursor: None,
+        })
     }
 
     pub async fn like(&self, pool: &PgPool) -> Result<(), ApiError> {
@@ -0,0 +1,157 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sqlx::{Executor, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    database::models::{DBPromo, DBPromoRevision, DBPromoStatus, DBRevisionAuthor},
+    routes::ApiError,
+    util::values::{MAX_DATETIME, MIN_DATETIME},
+};
+
+use super::{CompanyActor, Cursor, Page, PageRequest, PromoTarget};
+
+/// Fields of a promo that businesses can change after creating it.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PromoSnapshot {
+    pub description: String,
+    pub image_url: Option<String>,
+    pub target: PromoTarget,
+    pub max_count: i32,
+    pub active_from: Option<DateTime<Utc>>,
+    pub active_until: Option<DateTime<Utc>>,
+    pub per_user_limit: Option<i32>,
+    pub cooldown: Option<i32>,
+    pub max_per_day: Option<i32>,
+    pub status: DBPromoStatus,
+}
+
+impl PromoSnapshot {
+    /// Describes every changed field as `{"field": {"old": .., "new": ..}}`.
+    pub fn diff(old: Option<&Self>, new: &Self) -> Map<String, Value> {
+        let old = match old.map(serde_json::to_value) {
+            Some(Ok(Value::Object(fields))) => fields,
+            _ => Map::new(),
+        };
+        let Ok(Value::Object(new)) = serde_json::to_value(new) else {
+            return Map::new();
+        };
+
+        new.into_iter()
+            .filter_map(|(field, new_value)| {
+                let old_value = old.get(&field).cloned().unwrap_or(Value::Null);
+                (old_value != new_value)
+                    .then(|| (field, json!({ "old": old_value, "new": new_value })))
+            })
+            .collect()
+    }
+}
+
+impl From<&DBPromo> for PromoSnapshot {
+    fn from(promo: &DBPromo) -> Self {
+        Self {
+            description: promo.description.clone(),
+            image_url: promo.image_url.clone(),
+            target: promo.target.clone().into(),
+            max_count: promo.max_count,
+            active_from: Some(promo.active_from).filter(|date| *date > MIN_DATETIME),
+            active_until: Some(promo.active_until).filter(|date| *date < MAX_DATETIME),
+            per_user_limit: promo.per_user_limit,
+            cooldown: promo.cooldown,
+            max_per_day: promo.max_per_day,
+            status: promo.status,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct PromoRevision {
+    #[serde(skip)]
+    pub id: Uuid,
+
+    pub revision: i32,
+
+    pub author: DBRevisionAuthor,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub member_id: Option<Uuid>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored_from: Option<i32>,
+
+    pub changes: Value,
+
+    pub snapshot: Value,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromoRevision {
+    /// Records the change of a promo from `old` to `new`. Nothing is recorded
+    /// if none of the editable fields changed.
+    pub async fn record(
+        actor: &CompanyActor,
+        old: Option<&PromoSnapshot>,
+        new: &DBPromo,
+        restored_from: Option<i32>,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<Option<Self>, ApiError> {
+        let snapshot = PromoSnapshot::from(new);
+        let changes = PromoSnapshot::diff(old, &snapshot);
+        if changes.is_empty() {
+            return Ok(None);
+        }
+
+        let revision = DBPromoRevision {
+            id: Uuid::now_v7(),
+            promo_id: new.id,
+            revision: 0,
+            author: actor.revision_author(),
+            member_id: actor.member_id,
+            restored_from,
+            changes: Value::Object(changes),
+            snapshot: serde_json::to_value(snapshot)?,
+            created_at: Utc::now(),
+        }
+        .insert(transaction)
+        .await?;
+
+        Ok(Some(revision.into_model()))
+    }
+
+    pub async fn get_pageable<'a, E>(
+        promo_id: Uuid,
+        page: &PageRequest,
+        executor: E,
+    ) -> Result<Page<Self>, ApiError>
+    where
+        E: Executor<'a, Database = Postgres> + Copy,
+    {
+        let (revisions, count) = DBPromoRevision::get_pageable(promo_id, page, executor).await?;
+
+        Ok(
+            Page::new(revisions, count, page, |revision| Cursor::new(revision.id))
+                .map(DBPromoRevision::into_model),
+        )
+    }
+
+    pub fn snapshot(&self) -> Result<PromoSnapshot, ApiError> {
+        Ok(serde_json::from_value(self.snapshot.clone())?)
+    }
+}
+
+impl From<DBPromoRevision> for PromoRevision {
+    fn from(db_revision: DBPromoRevision) -> Self {
+        Self {
+            id: db_revision.id,
+            revision: db_revision.revision,
+            author: db_revision.author,
+            member_id: db_revision.member_id,
+            restored_from: db_revision.restored_from,
+            changes: db_revision.changes,
+            snapshot: db_revision.snapshot,
+            created_at: db_revision.created_at,
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::convert::Infallible;
+
+use actix_web::{dev::Payload, http::header::USER_AGENT, FromRequest, HttpRequest};
+use chrono::{DateTime, Utc};
+use futures::future::{ready, Ready};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::AuthenticationError,
+    database::{
+        models::{DBSession, DBToken, DatabaseError},
+        redis::RedisConnection,
+    },
+    routes::ApiError,
+};
+
+use super::{RefreshToken, Token, TokenType};
+
+const USER_AGENT_MAX_LENGTH: usize = 256;
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct SessionPath {
+    pub session_id: Uuid,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl FromRequest for ClientInfo {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let user_agent = req
+            .headers()
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(USER_AGENT_MAX_LENGTH).collect());
+
+        let ip = req
+            .connection_info()
+            .realip_remote_addr()
+            .map(str::to_string);
+
+        ready(Ok(Self { user_agent, ip }))
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct Session {
+    pub id: Uuid,
+
+    pub issued_at: DateTime<Utc>,
+
+    pub last_seen: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+
+    pub current: bool,
+}
+
+impl Session {
+    pub async fn start(
+        token_type: TokenType,
+        entity: Uuid,
+        client: ClientInfo,
+        cache: &mut RedisConnection,
+    ) -> Result<(Token, RefreshToken), DatabaseError> {
+        let id = Uuid::now_v7();
+        let token = Token::new(token_type, entity, id);
+        let refresh_token = RefreshToken::new(id, 0);
+
+        DBSession {
+            id,
+            entity: token.entity,
+            token_type: token.token_type.clone(),
+            token_id: token.id,
+            refresh_generation: refresh_token.generation,
+            refresh_hash: refresh_token.hash(),
+            previous_refresh_hashes: vec![],
+            issued_at: token.issued_at,
+            last_seen: token.issued_at,
+            user_agent: client.user_agent,
+            ip: client.ip,
+        }
+        .insert(cache)
+        .await?;
+
+        Ok((token, refresh_token))
+    }
+
+    pub async fn refresh(
+        refresh_token: RefreshToken,
+        allowed_tokens: &[TokenType],
+        cache: &mut RedisConnection,
+    ) -> Result<(Token, RefreshToken), AuthenticationError> {
+        let Some((mut session, stored)) =
+            DBSession::get_for_update(refresh_token.session_id, cache).await?
+        else {
+            return Err(AuthenticationError::InvalidCredentials);
+        };
+
+        if !allowed_tokens.contains(&session.token_type) {
+            return Err(AuthenticationError::IcorrectTokenType);
+        }
+
+        // Only a genuine earlier token proves the family leaked, anything
+        // else could be forged from the session id in an access token.
+        if session.is_previous_refresh(&refresh_token) {
+            session.delete(cache).await?;
+            return Err(AuthenticationError::RefreshTokenReused);
+        }
+
+        if refresh_token.generation != session.refresh_generation
+            || refresh_token.hash() != session.refresh_hash
+        {
+            return Err(AuthenticationError::InvalidCredentials);
+        }
+
+        let revoked_token_id = session.token_id;
+
+        let token = Token::new(session.token_type.clone(), session.entity, session.id);
+
+        let refresh_token = RefreshToken::new(session.id, session.refresh_generation + 1);
+
+        session.token_id = token.id;
+        session.rotate_refresh(&refresh_token);
+        session.last_seen = token.issued_at;
+
+        // Another refresh with the same token got there first, so it's reused
+        if !session.replace(&stored, cache).await? {
+            if let Some(session) = DBSession::get(session.id, cache).await? {
+                session.delete(cache).await?;
+            }
+            return Err(AuthenticationError::RefreshTokenReused);
+        }
+
+        DBToken::revoke(revoked_token_id, cache).await?;
+
+        Ok((token, refresh_token))
+    }
+
+    pub async fn get_all(
+        token: &Token,
+        cache: &mut RedisConnection,
+    ) -> Result<Vec<Self>, DatabaseError> {
+        Ok(DBSession::get_all(token.entity, cache)
+            .await?
+            .into_iter()
+            .map(|session| {
+                let mut session = session.into_model();
+                session.current = session.id == token.session_id;
+                session
+            })
+            .collect())
+    }
+
+    pub async fn revoke(
+        token: &Token,
+        session_id: Uuid,
+        cache: &mut RedisConnection,
+    ) -> Result<(), ApiError> {
+        match DBSession::get(session_id, cache).await? {
+            Some(session) if session.entity == token.entity => {
+                session.delete(cache).await?;
+                Ok(())
+            }
+            _ => Err(ApiError::NotFound),
+        }
+    }
+
+    pub async fn revoke_all(token: &Token, cache: &mut RedisConnection) -> Result<(), ApiError> {
+        DBSession::delete_all(token.entity, cache).await?;
+        Ok(())
+    }
+}
+
+impl From<DBSession> for Session {
+    fn from(db_session: DBSession) -> Self {
+        Self {
+            id: db_session.id,
+            issued_at: db_session.issued_at,
+            last_seen: db_session.last_seen,
+            user_agent: db_session.user_agent,
+            ip: db_session.ip,
+            current: false,
+        }
+    }
+}
@@ -1,8 +1,12 @@
-use serde::Serialize;
+use chrono::{DateTime, Datelike, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::{Executor, Postgres};
+use strum_macros::{Display, IntoStaticStr};
 use uuid::Uuid;
 
-use crate::database::models::{DBCountryStats, DatabaseError};
+use crate::database::models::{
+    DBActivationBucket, DBCompanyPromoStats, DBCountryStats, DBRedemptionStats, DatabaseError,
+};
 
 #[derive(Serialize, Clone, Debug)]
 pub struct PromoStatsCountry {
@@ -19,16 +23,28 @@ impl From<DBCountryStats> for PromoStatsCountry {
     }
 }
 
+const TOP_COUNTRIES_LIMIT: i64 = 10;
+
+fn conversion(activations_count: i64, unique_viewers: i64) -> f64 {
+    if unique_viewers == 0 {
+        0.0
+    } else {
+        activations_count as f64 / unique_viewers as f64
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct PromoStats {
     pub activations_count: i64,
+    pub issued_count: i64,
+    pub redeemed_count: i64,
     pub countries: Vec<PromoStatsCountry>,
 }
 
 impl PromoStats {
     pub async fn get<'a, E>(promo_id: Uuid, executor: E) -> Result<Self, DatabaseError>
     where
-        E: Executor<'a, Database = Postgres>,
+        E: Executor<'a, Database = Postgres> + Copy,
     {
         let countries: Vec<PromoStatsCountry> = DBCountryStats::get_all(promo_id, executor)
             .await?
@@ -42,9 +58,236 @@ impl PromoStats {
             .map(|c| c.activations_count)
             .sum();
 
+        let redemptions = DBRedemptionStats::get(promo_id, executor).await?;
+
         Ok(Self {
             activations_count,
+            issued_count: redemptions.issued_count,
+            redeemed_count: redemptions.redeemed_count,
             countries,
         })
     }
 }
+
+#[derive(
+    Deserialize, Serialize, Display, IntoStaticStr, Clone, Copy, PartialEq, Eq, Debug, Default,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum StatsBucket {
+    Hour,
+    #[default]
+    Day,
+    Week,
+}
+
+impl StatsBucket {
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::weeks(1),
+        }
+    }
+
+    pub fn truncate(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        let day = date.duration_trunc(Duration::days(1)).unwrap_or(date);
+        match self {
+            Self::Hour => date.duration_trunc(Duration::hours(1)).unwrap_or(date),
+            Self::Day => day,
+            Self::Week => day - Duration::days(day.weekday().num_days_from_monday().into()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsSplit {
+    Country,
+    Age,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PromoTimeseriesBreakdown {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_band: Option<String>,
+
+    pub activations_count: i64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PromoTimeseriesPoint {
+    pub start: DateTime<Utc>,
+
+    pub activations_count: i64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<Vec<PromoTimeseriesBreakdown>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PromoTimeseries {
+    pub bucket: StatsBucket,
+
+    pub from: DateTime<Utc>,
+
+    pub to: DateTime<Utc>,
+
+    pub points: Vec<PromoTimeseriesPoint>,
+}
+
+impl PromoTimeseries {
+    pub async fn get<'a, E>(
+        promo_id: Uuid,
+        bucket: StatsBucket,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        split_by: &[StatsSplit],
+        executor: E,
+    ) -> Result<Self, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        let by_country = split_by.contains(&StatsSplit::Country);
+        let by_age = split_by.contains(&StatsSplit::Age);
+        let from = bucket.truncate(from);
+
+        let mut rows =
+            DBActivationBucket::get_all(promo_id, bucket, from, to, by_country, by_age, executor)
+                .await?
+                .into_iter()
+                .peekable();
+
+        let mut points = vec![];
+        let mut start = from;
+        while start < to {
+            let mut point = PromoTimeseriesPoint {
+                start,
+                activations_count: 0,
+                breakdown: (by_country || by_age).then(Vec::new),
+            };
+
+            while let Some(row) = rows.next_if(|row| row.bucket == start) {
+                point.activations_count += row.activations_count;
+                if let Some(breakdown) = &mut point.breakdown {
+                    breakdown.push(PromoTimeseriesBreakdown {
+                        country: row.country,
+                        age_band: row.age_band,
+                        activations_count: row.activations_count,
+                    });
+                }
+            }
+
+            points.push(point);
+            start += bucket.duration();
+        }
+
+        Ok(Self {
+            bucket,
+            from,
+            to,
+            points,
+        })
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct StatsFunnel {
+    pub impressions: i64,
+    pub views: i64,
+    pub activations: i64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompanyPromoStats {
+    pub promo_id: Uuid,
+    pub description: String,
+    pub active: bool,
+    pub activations_count: i64,
+    pub likes_count: i64,
+    pub comments_count: i64,
+    pub unique_viewers: i64,
+    pub conversion: f64,
+    pub funnel: StatsFunnel,
+}
+
+impl From<DBCompanyPromoStats> for CompanyPromoStats {
+    fn from(db_stats: DBCompanyPromoStats) -> Self {
+        Self {
+            promo_id: db_stats.promo_id,
+            description: db_stats.description,
+            active: db_stats.active,
+            activations_count: db_stats.activations_count,
+            likes_count: db_stats.likes_count,
+            comments_count: db_stats.comments_count,
+            unique_viewers: db_stats.unique_viewers,
+            conversion: conversion(db_stats.activations_count, db_stats.unique_viewers),
+            funnel: StatsFunnel {
+                impressions: db_stats.impressions_count,
+                views: db_stats.views_count,
+                activations: db_stats.activations_count,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct CompanyStats {
+    pub promos_count: i64,
+    pub active_count: i64,
+    pub inactive_count: i64,
+    pub activations_count: i64,
+    pub likes_count: i64,
+    pub comments_count: i64,
+    pub unique_viewers: i64,
+    pub conversion: f64,
+    pub funnel: StatsFunnel,
+    pub top_countries: Vec<PromoStatsCountry>,
+    pub promos: Vec<CompanyPromoStats>,
+}
+
+impl CompanyStats {
+    pub async fn get<'a, E>(company_id: Uuid, executor: E) -> Result<Self, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres> + Copy,
+    {
+        let promos: Vec<CompanyPromoStats> = DBCompanyPromoStats::get_all(company_id, executor)
+            .await?
+            .into_iter()
+            .map(DBCompanyPromoStats::into_model)
+            .collect();
+
+        let unique_viewers = DBCompanyPromoStats::unique_viewers(company_id, executor).await?;
+
+        let top_countries =
+            DBCountryStats::get_top_for_company(company_id, TOP_COUNTRIES_LIMIT, executor)
+                .await?
+                .into_iter()
+                .map(DBCountryStats::into_model)
+                .collect();
+
+        let active_count = promos.iter().filter(|promo| promo.active).count() as i64;
+        let activations_count = promos.iter().map(|promo| promo.activations_count).sum();
+
+        Ok(Self {
+            promos_count: promos.len() as i64,
+            active_count,
+            inactive_count: promos.len() as i64 - active_count,
+            activations_count,
+            likes_count: promos.iter().map(|promo| promo.likes_count).sum(),
+            comments_count: promos.iter().map(|promo| promo.comments_count).sum(),
+            unique_viewers,
+            conversion: conversion(activations_count, unique_viewers),
+            funnel: StatsFunnel {
+                impressions: promos.iter().map(|promo| promo.funnel.impressions).sum(),
+                views: promos.iter().map(|promo| promo.funnel.views).sum(),
+                activations: activations_count,
+            },
+            top_countries,
+            promos,
+        })
+    }
+}
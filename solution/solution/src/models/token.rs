@@ -1,18 +1,30 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
 use crate::{
-    auth::AuthenticationError,
+    auth::{AuthenticationError, SigningKeys},
     database::{
-        models::{DBCompany, DBToken, DBUser, DatabaseError},
-        redis::RedisConnection,
+        models::{DBCompany, DBSession, DBToken, DBUser, DatabaseError},
+        redis::{RedisConnection, RedisPool},
     },
     util::convertions::{decode_string, decode_uuid},
+    ACCESS_TOKEN_LIFETIME, REFRESH_TOKEN_LIFETIME,
 };
 use base64::{
     alphabet::URL_SAFE,
     engine::{general_purpose::NO_PAD, GeneralPurpose},
     Engine,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use log::warn;
+use rand_chacha::{
+    rand_core::{RngCore, SeedableRng},
+    ChaCha20Rng,
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::{Executor, Postgres};
 use strum_macros::{Display, EnumString, IntoStaticStr};
 use uuid::Uuid;
@@ -27,6 +39,8 @@ pub enum TokenType {
     Company,
     #[strum(serialize = "usr")]
     User,
+    #[strum(serialize = "mbr")]
+    Member,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -34,6 +48,7 @@ pub struct Token {
     pub id: Uuid,
     pub token_type: TokenType,
     pub entity: Uuid,
+    pub session_id: Uuid,
     pub issued_at: DateTime<Utc>,
 }
 
@@ -41,26 +56,56 @@ impl Token {
     const ENGINE: GeneralPurpose =
         GeneralPurpose::new(&URL_SAFE, NO_PAD.with_decode_allow_trailing_bits(true));
 
-    pub fn new(token_type: TokenType, entity: Uuid) -> Self {
+    pub fn new(token_type: TokenType, entity: Uuid, session_id: Uuid) -> Self {
         Self {
             id: Uuid::now_v7(),
             token_type,
             entity,
+            session_id,
             issued_at: Utc::now(),
         }
     }
 
+    pub fn lifetime() -> Duration {
+        Duration::seconds(
+            ACCESS_TOKEN_LIFETIME()
+                .parse()
+                .expect("`ACCESS_TOKEN_LIFETIME` must be a number of seconds"),
+        )
+    }
+
     pub fn is_expired(&self) -> bool {
-        Utc::now() - self.issued_at >= chrono::Duration::days(1)
+        Utc::now() - self.issued_at >= Self::lifetime()
     }
 
     pub async fn validate(self, cache: &mut RedisConnection) -> Result<Self, AuthenticationError> {
-        Ok(self
-            .into_db()
-            .validate(cache)
-            .await
-            .map_err(|_| AuthenticationError::InvalidCredentials)?
-            .into_model())
+        if self.is_expired() {
+            return Err(AuthenticationError::ExpiredToken);
+        }
+
+        if DBToken::is_revoked(self.id, cache).await? {
+            return Err(AuthenticationError::InvalidCredentials);
+        }
+
+        Ok(self)
+    }
+
+    /// Updates the session's last seen time in the background, so the request
+    /// does not wait on it.
+    pub fn touch_session(&self, cache: &RedisPool) {
+        let session_id = self.session_id;
+        let cache = cache.clone();
+
+        actix_rt::spawn(async move {
+            let result = match cache.connect().await {
+                Ok(mut cache) => DBSession::touch(session_id, &mut cache).await,
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                warn!("Touching session {} failed: {:?}", session_id, e);
+            }
+        });
     }
 
     pub async fn get_company<'a, E>(&self, executor: E) -> Result<Company, DatabaseError>
@@ -83,47 +128,72 @@ impl Token {
             .into_model())
     }
 
-    pub fn from_str(value: &str) -> Result<Self, ()> {
-        let parts: Vec<&str> = value.split('.').collect();
-        if parts.len() != 4 {
+    pub fn into_db(self) -> DBToken {
+        DBToken::from(self)
+    }
+}
+
+impl Token {
+    fn payload(&self, key_id: &str) -> String {
+        format!(
+            "{}.{}.{}.{}.{}.{}",
+            Self::ENGINE.encode(self.id),
+            self.token_type,
+            Self::ENGINE.encode(self.entity),
+            Self::ENGINE.encode(self.session_id),
+            Self::ENGINE.encode(self.issued_at.timestamp_millis().to_string()),
+            key_id
+        )
+    }
+}
+
+impl FromStr for Token {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (payload, signature) = value.rsplit_once('.').ok_or(())?;
+
+        let parts: Vec<&str> = payload.split('.').collect();
+        if parts.len() != 6 {
+            return Err(());
+        }
+
+        let key = SigningKeys::get().find(parts[5]).ok_or(())?;
+        let signature = Self::ENGINE.decode(signature).map_err(|_| ())?;
+        if !key.verify(payload.as_bytes(), &signature) {
             return Err(());
         }
 
         let id = decode_uuid(&Self::ENGINE, parts[0])?;
 
-        let token_type = parts[1].to_owned().parse().map_err(|_| ())?;
+        let token_type = parts[1].parse().map_err(|_| ())?;
 
         let entity = decode_uuid(&Self::ENGINE, parts[2])?;
 
-        let issued_at = decode_string(&Self::ENGINE, parts[3])?
+        let session_id = decode_uuid(&Self::ENGINE, parts[3])?;
+
+        let issued_at = decode_string(&Self::ENGINE, parts[4])?
             .parse::<i64>()
             .map_err(|_| ())?;
-        if let Some(issued_at) = DateTime::<Utc>::from_timestamp_millis(issued_at) {
-            Ok(Self {
-                id,
-                token_type,
-                entity,
-                issued_at,
-            })
-        } else {
-            Err(())
-        }
-    }
+        let issued_at = DateTime::<Utc>::from_timestamp_millis(issued_at).ok_or(())?;
 
-    pub fn into_db(self) -> DBToken {
-        DBToken::from(self)
+        Ok(Self {
+            id,
+            token_type,
+            entity,
+            session_id,
+            issued_at,
+        })
     }
 }
 
-impl ToString for Token {
-    fn to_string(&self) -> String {
-        format!(
-            "{}.{}.{}.{}",
-            Self::ENGINE.encode(&self.id),
-            self.token_type.to_string(),
-            Self::ENGINE.encode(&self.entity),
-            Self::ENGINE.encode(self.issued_at.timestamp_millis().to_string())
-        )
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let key = SigningKeys::get().current();
+        let payload = self.payload(&key.id);
+        let signature = key.sign(payload.as_bytes());
+
+        write!(f, "{}.{}", payload, Self::ENGINE.encode(signature))
     }
 }
 
@@ -133,26 +203,194 @@ impl From<DBToken> for Token {
             DBToken {
                 id,
                 company_id: Some(entity),
+                session_id,
                 issued_at,
                 ..
             } => Self {
                 id,
                 token_type: TokenType::Company,
                 entity,
+                session_id,
                 issued_at,
             },
             DBToken {
                 id,
                 user_id: Some(entity),
+                session_id,
                 issued_at,
                 ..
             } => Self {
                 id,
                 token_type: TokenType::User,
                 entity,
+                session_id,
+                issued_at,
+            },
+            DBToken {
+                id,
+                member_id: Some(entity),
+                session_id,
+                issued_at,
+                ..
+            } => Self {
+                id,
+                token_type: TokenType::Member,
+                entity,
+                session_id,
                 issued_at,
             },
             _ => unreachable!(),
         }
     }
 }
+
+#[derive(Clone, Debug)]
+pub struct RefreshToken {
+    pub session_id: Uuid,
+    pub generation: u32,
+    pub secret: String,
+}
+
+const SECRET_LENGTH: usize = 32;
+
+pub(super) fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    ChaCha20Rng::from_entropy().fill_bytes(&mut secret);
+    Token::ENGINE.encode(secret)
+}
+
+fn hash_secret(secret: &str) -> String {
+    Token::ENGINE.encode(Sha256::digest(secret.as_bytes()))
+}
+
+impl RefreshToken {
+    pub fn new(session_id: Uuid, generation: u32) -> Self {
+        Self {
+            session_id,
+            generation,
+            secret: generate_secret(),
+        }
+    }
+
+    pub fn lifetime() -> Duration {
+        Duration::seconds(
+            REFRESH_TOKEN_LIFETIME()
+                .parse()
+                .expect("`REFRESH_TOKEN_LIFETIME` must be a number of seconds"),
+        )
+    }
+
+    pub fn hash(&self) -> String {
+        hash_secret(&self.secret)
+    }
+}
+
+impl FromStr for RefreshToken {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = value.split('.').collect();
+        if parts.len() != 3 {
+            return Err(());
+        }
+
+        Ok(Self {
+            session_id: decode_uuid(&Token::ENGINE, parts[0])?,
+            generation: parts[1].parse().map_err(|_| ())?,
+            secret: parts[2].to_string(),
+        })
+    }
+}
+
+impl Display for RefreshToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            Token::ENGINE.encode(self.session_id),
+            self.generation,
+            self.secret
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct InviteToken {
+    pub member_id: Uuid,
+    pub secret: String,
+}
+
+impl InviteToken {
+    pub fn new(member_id: Uuid) -> Self {
+        Self {
+            member_id,
+            secret: generate_secret(),
+        }
+    }
+
+    pub fn hash(&self) -> String {
+        hash_secret(&self.secret)
+    }
+}
+
+impl FromStr for InviteToken {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (member_id, secret) = value.split_once('.').ok_or(())?;
+
+        Ok(Self {
+            member_id: decode_uuid(&Token::ENGINE, member_id)?,
+            secret: secret.to_string(),
+        })
+    }
+}
+
+impl Display for InviteToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}",
+            Token::ENGINE.encode(self.member_id),
+            self.secret
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ApiKeyToken {
+    pub key_id: Uuid,
+    pub secret: String,
+}
+
+impl ApiKeyToken {
+    pub fn new(key_id: Uuid) -> Self {
+        Self {
+            key_id,
+            secret: generate_secret(),
+        }
+    }
+
+    pub fn hash(&self) -> String {
+        hash_secret(&self.secret)
+    }
+}
+
+impl FromStr for ApiKeyToken {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (key_id, secret) = value.split_once('.').ok_or(())?;
+
+        Ok(Self {
+            key_id: decode_uuid(&Token::ENGINE, key_id)?,
+            secret: secret.to_string(),
+        })
+    }
+}
+
+impl Display for ApiKeyToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", Token::ENGINE.encode(self.key_id), self.secret)
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use futures::{stream::FuturesOrdered, TryStreamExt};
 use serde::{Deserialize, Serialize};
 use sqlx::{Executor, Postgres};
@@ -5,12 +6,12 @@ use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    database::models::{DBPromoActivation, DBUser, DBUserTargetSettings},
+    database::models::{DBPromo, DBPromoActivation, DBUser, DBUserTargetSettings, DatabaseError},
     routes::ApiError,
     util::validate::validate_country,
 };
 
-use super::{comment::CommentAuthor, PromoTarget, UserPromo};
+use super::{comment::CommentAuthor, Cursor, Page, PageRequest, UserPromo};
 
 #[derive(Deserialize, Serialize, Validate, Clone, Debug)]
 pub struct UserTargetSettings {
@@ -45,47 +46,60 @@ pub struct User {
     pub avatar_url: Option<String>,
     pub other: UserTargetSettings,
     pub password_hash: String,
+    pub interests: Vec<String>,
 }
 
 impl User {
-    pub fn matches_target(&self, target: PromoTarget) -> bool {
-        let mut matches = true;
-
-        matches &= target.country.is_none() || target.country.unwrap() == self.other.country;
-
-        matches &= target.age_from.is_none() || target.age_from.unwrap() <= self.other.age;
-        matches &= target.age_until.is_none() || target.age_until.unwrap() >= self.other.age;
+    pub async fn get_interests<'a, E>(&self, executor: E) -> Result<Vec<String>, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        DBUser::get_interests(self.id, executor).await
+    }
 
-        matches
+    pub async fn matches_target<'a, E>(
+        &self,
+        promo_id: Uuid,
+        executor: E,
+    ) -> Result<bool, DatabaseError>
+    where
+        E: Executor<'a, Database = Postgres>,
+    {
+        DBPromo::matches_target(promo_id, self.id, executor).await
     }
 
     pub async fn get_activation_history<'a, E>(
         &self,
-        limit: Option<u32>,
-        offset: Option<u32>,
+        page: &PageRequest,
         executor: E,
-    ) -> Result<(Vec<UserPromo>, i64), ApiError>
+    ) -> Result<Page<UserPromo>, ApiError>
     where
         E: Executor<'a, Database = Postgres> + Copy,
     {
-        let limit: i64 = match limit {
-            Some(limit) if limit > 57 => 57,
-            Some(limit) => limit.into(),
-            None => 10,
-        };
-
-        let (db_promos, count) =
-            DBPromoActivation::get_history(self.id, limit, offset.unwrap_or(0).into(), executor)
-                .await?;
-
-        let promos: Vec<UserPromo> = db_promos
+        let (entries, count) = DBPromoActivation::get_history(self.id, page, executor).await?;
+
+        let history = Page::new(
+            entries.into_iter().map(|e| e.into_parts()).collect(),
+            count,
+            page,
+            |(promo, activated_at): &(DBPromo, DateTime<Utc>)| {
+                Cursor::with_date(*activated_at, promo.id)
+            },
+        );
+
+        let promos: Vec<UserPromo> = history
+            .items
             .into_iter()
-            .map(|p| p.into_model().into_user(self.id, executor))
+            .map(|(p, _)| p.into_model().into_user(self.id, executor))
             .collect::<FuturesOrdered<_>>()
             .try_collect()
             .await?;
 
-        Ok((promos, count))
+        Ok(Page {
+            items: promos,
+            total: history.total,
+            next_cursor: history.next_cursor,
+        })
     }
 
     pub fn into_db(self) -> DBUser {
@@ -107,6 +121,7 @@ impl From<DBUser> for User {
             avatar_url: db_user.avatar_url.map(|url| url.to_string()),
             other: db_user.other.into_model(),
             password_hash: db_user.password_hash,
+            interests: db_user.interests,
         }
     }
 }
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use strum_macros::{Display, EnumString, IntoStaticStr};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::database::models::{DBWebhook, DBWebhookDelivery, DBWebhookDeliveryStatus};
+
+use super::token::generate_secret;
+
+#[derive(Deserialize, Validate, Debug)]
+pub struct WebhookPath {
+    pub webhook_id: Uuid,
+}
+
+#[derive(
+    Deserialize, Serialize, Clone, Copy, Display, PartialEq, Eq, EnumString, IntoStaticStr, Debug,
+)]
+pub enum WebhookEvent {
+    #[serde(rename = "promo.activated")]
+    #[strum(serialize = "promo.activated")]
+    PromoActivated,
+
+    #[serde(rename = "promo.liked")]
+    #[strum(serialize = "promo.liked")]
+    PromoLiked,
+
+    #[serde(rename = "promo.commented")]
+    #[strum(serialize = "promo.commented")]
+    PromoCommented,
+
+    #[serde(rename = "promo.exhausted")]
+    #[strum(serialize = "promo.exhausted")]
+    PromoExhausted,
+
+    #[serde(rename = "promo.expired")]
+    #[strum(serialize = "promo.expired")]
+    PromoExpired,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Webhook {
+    pub id: Uuid,
+
+    #[serde(skip)]
+    pub company_id: Uuid,
+
+    pub url: String,
+
+    #[serde(skip)]
+    pub secret: String,
+
+    pub events: Vec<WebhookEvent>,
+
+    pub active: bool,
+
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn generate_secret() -> String {
+        format!("whsec_{}", generate_secret())
+    }
+}
+
+impl From<DBWebhook> for Webhook {
+    fn from(db_webhook: DBWebhook) -> Self {
+        Self {
+            id: db_webhook.id,
+            company_id: db_webhook.company_id,
+            url: db_webhook.url,
+            secret: db_webhook.secret,
+            events: db_webhook
+                .events
+                .iter()
+                .filter_map(|event| event.parse().ok())
+                .collect(),
+            active: db_webhook.active,
+            created_at: db_webhook.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+
+    pub event: String,
+
+    pub payload: Value,
+
+    pub status: DBWebhookDeliveryStatus,
+
+    pub attempts: i32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_status: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl From<DBWebhookDelivery> for WebhookDelivery {
+    fn from(db_delivery: DBWebhookDelivery) -> Self {
+        Self {
+            id: db_delivery.id,
+            event: db_delivery.event,
+            payload: db_delivery.payload,
+            status: db_delivery.status,
+            attempts: db_delivery.attempts,
+            response_status: db_delivery.response_status,
+            last_error: db_delivery.last_error,
+            created_at: db_delivery.created_at,
+            next_attempt_at: (db_delivery.status == DBWebhookDeliveryStatus::PENDING)
+                .then_some(db_delivery.next_attempt_at),
+            last_attempt_at: db_delivery.last_attempt_at,
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use actix_web::{
+    delete, get,
+    middleware::from_fn,
+    post,
+    web::{scope, Data, Json, Path, ReqData, ServiceConfig},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::auth_middleware_cmp,
+    database::models::DBApiKey,
+    models::{
+        ApiKey, ApiKeyPath, ApiKeyScope, ApiKeyToken, CompanyActor, CompanyPermission,
+        EmptyResponse,
+    },
+    routes::ApiError,
+    util::{cors::default_cors, validate::validation_errors_to_string},
+};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("api-keys")
+            .wrap(default_cors())
+            .wrap(from_fn(auth_middleware_cmp))
+            .service(get_handler)
+            .service(post_handler)
+            .service(delete_handler),
+    );
+}
+
+#[get("")]
+async fn get_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+) -> Result<Json<Vec<ApiKey>>, ApiError> {
+    actor.require(CompanyPermission::ApiKeysManage)?;
+
+    let api_keys = DBApiKey::get_all(actor.company_id, &**pool)
+        .await?
+        .into_iter()
+        .map(DBApiKey::into_model)
+        .collect();
+
+    Ok(Json(api_keys))
+}
+
+#[derive(Deserialize, Validate, Debug)]
+struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+
+    #[validate(length(min = 1))]
+    scopes: Vec<ApiKeyScope>,
+
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[post("")]
+async fn post_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    body: Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::ApiKeysManage)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+    let now = Utc::now();
+
+    if body.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(ApiError::InvalidInput(
+            "`expires_at` must be in the future".to_string(),
+        ));
+    }
+
+    let mut scopes: Vec<String> = body.scopes.iter().map(ApiKeyScope::to_string).collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let api_key = ApiKeyToken::new(Uuid::now_v7());
+
+    let mut transaction = pool.begin().await?;
+
+    let db_api_key = DBApiKey {
+        id: api_key.key_id,
+        company_id: actor.company_id,
+        name: body.name.clone(),
+        key_hash: api_key.hash(),
+        scopes,
+        created_at: now,
+        expires_at: body.expires_at,
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(HttpResponse::Created().json(CreateApiKeyResponse {
+        key: api_key.to_string(),
+        api_key: db_api_key.into_model(),
+    }))
+}
+
+#[derive(Serialize, Debug)]
+struct CreateApiKeyResponse {
+    key: String,
+
+    #[serde(flatten)]
+    api_key: ApiKey,
+}
+
+#[delete("/{api_key_id}")]
+async fn delete_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<ApiKeyPath>,
+) -> Result<EmptyResponse, ApiError> {
+    actor.require(CompanyPermission::ApiKeysManage)?;
+
+    let api_key = match DBApiKey::get_by_id(path.api_key_id, &**pool).await? {
+        Some(api_key) if api_key.company_id == actor.company_id => api_key,
+        _ => return Err(ApiError::NotFound),
+    };
+
+    let mut transaction = pool.begin().await?;
+
+    api_key.delete(&mut transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(EmptyResponse::default())
+}
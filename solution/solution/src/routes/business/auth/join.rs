@@ -0,0 +1,81 @@
+use actix_web::{
+    post,
+    web::{Data, Json},
+};
+use argon2::{password_hash::SaltString, Argon2, PasswordHasher};
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::{
+    auth::AuthenticationError,
+    database::{models::DBCompanyMember, redis::RedisPool},
+    models::{ClientInfo, InviteToken, Session, TokenType},
+    routes::ApiError,
+    util::validate::{validate_password, validation_errors_to_string},
+};
+
+#[derive(Deserialize, Validate, Debug)]
+struct JoinRequest {
+    invite_token: String,
+
+    #[validate(length(min = 1, max = 100))]
+    name: String,
+
+    #[validate(custom(function = "validate_password"), length(min = 8, max = 256))]
+    password: String,
+}
+
+#[post("join")]
+pub async fn post_handler(
+    pool: Data<PgPool>,
+    cache: Data<RedisPool>,
+    client: ClientInfo,
+    body: Json<JoinRequest>,
+) -> Result<Json<JoinResponse>, ApiError> {
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+    let invite: InviteToken = body
+        .invite_token
+        .parse()
+        .map_err(|_| AuthenticationError::InvalidCredentials)?;
+
+    let member = match DBCompanyMember::get_by_id(invite.member_id, &**pool).await? {
+        Some(member) if member.invite_hash.as_deref() == Some(&invite.hash()) => member,
+        _ => return Err(AuthenticationError::InvalidCredentials)?,
+    };
+
+    let hasher = Argon2::default();
+    let salt = SaltString::generate(&mut ChaCha20Rng::from_entropy());
+    let password_hash = hasher
+        .hash_password(body.password.as_bytes(), &salt)?
+        .to_string();
+
+    let mut transaction = pool.begin().await?;
+
+    let member = member
+        .accept(&body.name, &password_hash, &mut transaction)
+        .await?;
+
+    transaction.commit().await?;
+
+    let mut cache = cache.connect().await?;
+
+    let (token, refresh_token) =
+        Session::start(TokenType::Member, member.id, client, &mut cache).await?;
+
+    Ok(Json(JoinResponse {
+        token: token.to_string(),
+        refresh_token: refresh_token.to_string(),
+        company_id: member.company_id.to_string(),
+    }))
+}
+
+#[derive(Serialize, Debug)]
+struct JoinResponse {
+    token: String,
+    refresh_token: String,
+    company_id: String,
+}
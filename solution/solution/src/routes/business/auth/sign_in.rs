@@ -9,8 +9,11 @@ use validator::Validate;
 
 use crate::{
     auth::AuthenticationError,
-    database::{models::DBCompany, redis::RedisPool},
-    models::{Token, TokenType},
+    database::{
+        models::{DBCompany, DBCompanyMember},
+        redis::RedisPool,
+    },
+    models::{ClientInfo, Session, TokenType},
     routes::ApiError,
     util::validate::validation_errors_to_string,
 };
@@ -28,38 +31,46 @@ struct SignInRequest {
 pub async fn post_handler(
     pool: Data<PgPool>,
     cache: Data<RedisPool>,
+    client: ClientInfo,
     body: Json<SignInRequest>,
 ) -> Result<Json<SignInResponse>, ApiError> {
     body.validate()
         .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
 
-    let company = if let Ok(Some(company)) = DBCompany::get_by_email(&body.email, &**pool).await {
-        company
-    } else {
-        return Err(AuthenticationError::InvalidCredentials)?;
-    };
+    let (token_type, entity, password_hash) =
+        if let Ok(Some(company)) = DBCompany::get_by_email(&body.email, &**pool).await {
+            (TokenType::Company, company.id, company.password_hash)
+        } else if let Ok(Some(DBCompanyMember {
+            id,
+            password_hash: Some(password_hash),
+            ..
+        })) = DBCompanyMember::get_by_email(&body.email, &**pool).await
+        {
+            (TokenType::Member, id, password_hash)
+        } else {
+            return Err(AuthenticationError::InvalidCredentials)?;
+        };
 
     let hasher = Argon2::default();
     hasher
         .verify_password(
             body.password.as_bytes(),
-            &PasswordHash::new(&company.password_hash)?,
+            &PasswordHash::new(&password_hash)?,
         )
         .map_err(|_| AuthenticationError::InvalidCredentials)?;
 
     let mut cache = cache.connect().await?;
 
-    let token = Token::new(TokenType::Company, company.id)
-        .into_db()
-        .insert(&mut cache)
-        .await?
-        .into_model()
-        .to_string();
+    let (token, refresh_token) = Session::start(token_type, entity, client, &mut cache).await?;
 
-    Ok(Json(SignInResponse { token }))
+    Ok(Json(SignInResponse {
+        token: token.to_string(),
+        refresh_token: refresh_token.to_string(),
+    }))
 }
 
 #[derive(Serialize, Debug)]
 struct SignInResponse {
     token: String,
+    refresh_token: String,
 }
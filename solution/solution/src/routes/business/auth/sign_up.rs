@@ -11,8 +11,11 @@ use validator::Validate;
 
 use crate::{
     auth::AuthenticationError,
-    database::{models::DBCompany, redis::RedisPool},
-    models::{Company, Token, TokenType},
+    database::{
+        models::{DBCompany, DBCompanyMember},
+        redis::RedisPool,
+    },
+    models::{ClientInfo, Company, Session, TokenType},
     routes::ApiError,
     util::validate::{validate_password, validation_errors_to_string},
 };
@@ -33,6 +36,7 @@ struct SignUpRequest {
 pub async fn post_handler(
     pool: Data<PgPool>,
     cache: Data<RedisPool>,
+    client: ClientInfo,
     body: Json<SignUpRequest>,
 ) -> Result<Json<SignUpResponse>, ApiError> {
     body.validate()
@@ -41,6 +45,9 @@ pub async fn post_handler(
     if DBCompany::get_by_email(&body.email, &**pool)
         .await?
         .is_some()
+        || DBCompanyMember::get_by_email(&body.email, &**pool)
+            .await?
+            .is_some()
     {
         return Err(AuthenticationError::DuplicateCompany)?;
     }
@@ -55,12 +62,7 @@ pub async fn post_handler(
     let mut transaction = pool.begin().await?;
     let mut cache = cache.connect().await?;
 
-    let token = Token::new(TokenType::Company, id)
-        .into_db()
-        .insert(&mut cache)
-        .await?
-        .into_model()
-        .to_string();
+    let (token, refresh_token) = Session::start(TokenType::Company, id, client, &mut cache).await?;
 
     let company = Company {
         id,
@@ -76,7 +78,8 @@ pub async fn post_handler(
     transaction.commit().await?;
 
     Ok(Json(SignUpResponse {
-        token,
+        token: token.to_string(),
+        refresh_token: refresh_token.to_string(),
         company_id: company.id.to_string(),
     }))
 }
@@ -84,5 +87,6 @@ pub async fn post_handler(
 #[derive(Serialize, Debug)]
 struct SignUpResponse {
     token: String,
+    refresh_token: String,
     company_id: String,
 }
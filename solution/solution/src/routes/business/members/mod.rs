@@ -0,0 +1,175 @@
+use actix_web::{
+    delete, get,
+    middleware::from_fn,
+    patch, post,
+    web::{scope, Data, Json, Path, ReqData, ServiceConfig},
+    HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::{auth_middleware_cmp, AuthenticationError},
+    database::{
+        models::{DBCompany, DBCompanyMember, DBCompanyRole, DBSession},
+        redis::RedisPool,
+    },
+    models::{
+        CompanyActor, CompanyMember, CompanyPermission, EmptyResponse, InviteToken, MemberPath,
+    },
+    routes::ApiError,
+    util::{cors::default_cors, validate::validation_errors_to_string},
+};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("members")
+            .wrap(default_cors())
+            .wrap(from_fn(auth_middleware_cmp))
+            .service(get_handler)
+            .service(post_handler)
+            .service(patch_handler)
+            .service(delete_handler),
+    );
+}
+
+async fn get_company_member(
+    actor: &CompanyActor,
+    member_id: Uuid,
+    pool: &PgPool,
+) -> Result<DBCompanyMember, ApiError> {
+    match DBCompanyMember::get_by_id(member_id, pool).await? {
+        Some(member) if member.company_id == actor.company_id => Ok(member),
+        _ => Err(ApiError::NotFound),
+    }
+}
+
+#[get("")]
+async fn get_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+) -> Result<Json<Vec<CompanyMember>>, ApiError> {
+    actor.require(CompanyPermission::MembersManage)?;
+
+    let members = DBCompanyMember::get_all(actor.company_id, &**pool)
+        .await?
+        .into_iter()
+        .map(DBCompanyMember::into_model)
+        .collect();
+
+    Ok(Json(members))
+}
+
+#[derive(Deserialize, Validate, Debug)]
+struct InviteMemberRequest {
+    #[validate(email, length(min = 8, max = 120))]
+    email: String,
+
+    role: DBCompanyRole,
+}
+
+#[post("")]
+async fn post_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    body: Json<InviteMemberRequest>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::MembersManage)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+    if DBCompany::get_by_email(&body.email, &**pool)
+        .await?
+        .is_some()
+        || DBCompanyMember::get_by_email(&body.email, &**pool)
+            .await?
+            .is_some()
+    {
+        return Err(AuthenticationError::DuplicateCompany)?;
+    }
+
+    let invite_token = InviteToken::new(Uuid::now_v7());
+
+    let mut transaction = pool.begin().await?;
+
+    let member = DBCompanyMember {
+        id: invite_token.member_id,
+        company_id: actor.company_id,
+        email: body.email.clone(),
+        name: None,
+        role: body.role,
+        password_hash: None,
+        invite_hash: Some(invite_token.hash()),
+        invited_at: Utc::now(),
+        joined_at: None,
+    }
+    .insert(&mut transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(HttpResponse::Created().json(InviteMemberResponse {
+        id: member.id,
+        invite_token: invite_token.to_string(),
+    }))
+}
+
+#[derive(Serialize, Debug)]
+struct InviteMemberResponse {
+    id: Uuid,
+    invite_token: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct EditMemberRequest {
+    role: DBCompanyRole,
+}
+
+#[patch("/{member_id}")]
+async fn patch_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<MemberPath>,
+    body: Json<EditMemberRequest>,
+) -> Result<Json<CompanyMember>, ApiError> {
+    actor.require(CompanyPermission::MembersManage)?;
+
+    let member = get_company_member(&actor, path.member_id, &pool).await?;
+
+    let mut transaction = pool.begin().await?;
+
+    let member = member.patch(body.role, &mut transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(member.into_model()))
+}
+
+#[delete("/{member_id}")]
+async fn delete_handler(
+    pool: Data<PgPool>,
+    cache: Data<RedisPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<MemberPath>,
+) -> Result<EmptyResponse, ApiError> {
+    actor.require(CompanyPermission::MembersManage)?;
+
+    let member = get_company_member(&actor, path.member_id, &pool).await?;
+    let member_id = member.id;
+
+    let mut transaction = pool.begin().await?;
+
+    member.delete(&mut transaction).await?;
+
+    transaction.commit().await?;
+
+    let mut cache = cache.connect().await?;
+
+    DBSession::delete_all(member_id, &mut cache).await?;
+
+    Ok(EmptyResponse::default())
+}
@@ -2,14 +2,24 @@ use actix_web::web::{scope, ServiceConfig};
 
 use crate::util::cors::default_cors;
 
+mod api_keys;
 mod auth;
+mod members;
 mod promo;
+mod redeem;
+mod stats;
+mod webhooks;
 
 pub fn config(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("business")
             .wrap(default_cors())
             .configure(auth::config)
-            .configure(promo::config),
+            .configure(api_keys::config)
+            .configure(members::config)
+            .configure(promo::config)
+            .configure(redeem::config)
+            .configure(stats::config)
+            .configure(webhooks::config),
     );
 }
@@ -0,0 +1,97 @@
+use actix_web::{
+    post,
+    web::{Data, Json, Path, ReqData},
+    HttpResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::PgPool;
+use validator::Validate;
+
+use crate::{
+    database::models::{DBPromoMode, DBPromoStatus},
+    models::{CompanyActor, CompanyPermission, PromoPath, PromoTarget},
+    routes::ApiError,
+    util::{convertions::promo_date_format, validate::validation_errors_to_string},
+};
+
+use super::{
+    super::create_promo::{CreatePromoRequest, PostPromoResponse},
+    codes::GenerateCodesRequest,
+    get_company_promo,
+};
+
+#[derive(Deserialize, Validate, Debug)]
+struct ClonePromoRequest {
+    #[serde(default, with = "promo_date_format")]
+    active_from: Option<DateTime<Utc>>,
+
+    #[serde(default, with = "promo_date_format")]
+    active_until: Option<DateTime<Utc>>,
+
+    #[validate(range(min = 0, max = 100000000))]
+    max_count: Option<i32>,
+
+    #[validate(length(min = 5, max = 30))]
+    promo_common: Option<String>,
+
+    /// Codes to generate for a UNIQUE promo, its pool stays empty otherwise.
+    #[validate(nested)]
+    codes: Option<GenerateCodesRequest>,
+}
+
+/// Copies a promo into a new draft campaign. The activity period isn't
+/// copied, while `max_count`, `promo_common` and activation limits are kept
+/// unless overridden.
+#[post("/clone")]
+pub async fn post_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+    Json(body): Json<ClonePromoRequest>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+    let source = get_company_promo(&actor, path.promo_id, &pool).await?;
+
+    if let Some(codes) = &body.codes {
+        if source.mode != DBPromoMode::UNIQUE {
+            return Err(ApiError::InvalidInput(
+                "field `codes` can only be used in UNIQUE promocodes".to_string(),
+            ));
+        }
+        codes.generator()?;
+    }
+
+    let request = CreatePromoRequest {
+        description: source.description,
+        image_url: source.image_url,
+        target: PromoTarget::from(source.target),
+        max_count: body.max_count.unwrap_or(source.max_count),
+        active_from: body.active_from,
+        active_until: body.active_until,
+        per_user_limit: source.per_user_limit,
+        cooldown: source.cooldown,
+        max_per_day: source.max_per_day,
+        mode: source.mode,
+        promo_common: body.promo_common.or(source.promo_common),
+        promo_unique: None,
+        status: Some(DBPromoStatus::DRAFT),
+    };
+    request.check()?;
+
+    let mut transaction = pool.begin().await?;
+
+    let promo = request.insert(&actor, &mut transaction).await?;
+
+    if let Some(codes) = &body.codes {
+        codes.generate(promo.id, &mut transaction).await?;
+    }
+
+    transaction.commit().await?;
+
+    Ok(HttpResponse::Created().json(PostPromoResponse { id: promo.id }))
+}
@@ -0,0 +1,332 @@
+use std::collections::HashSet;
+
+use actix_web::{
+    get, post,
+    web::{Data, Json, Path, Payload, Query, ReqData},
+    HttpRequest, HttpResponse,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    database::models::{DBPromo, DBPromoCode, DBPromoMode, DBPromoStatus},
+    models::{CompanyActor, CompanyPermission, Page, PageRequest, PromoPath},
+    routes::ApiError,
+    util::{
+        codes::{
+            parse_code_line, validate_code, CodeGenerator, DEFAULT_CODE_ALPHABET,
+            DEFAULT_CODE_LENGTH, MAX_CODE_LENGTH,
+        },
+        validate::validation_errors_to_string,
+    },
+};
+
+use super::{get_company_promo, lock_company_promo};
+
+const MAX_CODES_PER_REQUEST: usize = 1_000_000;
+const MAX_LINE_LENGTH: usize = 1024;
+const INSERT_BATCH_SIZE: usize = 5000;
+
+/// Locks the promo for the rest of the transaction, so codes added by
+/// concurrent requests get distinct positions, and checks that codes can be
+/// added to it.
+async fn lock_unique_promo(
+    actor: &CompanyActor,
+    promo_id: Uuid,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<DBPromo, ApiError> {
+    let promo = lock_company_promo(actor, promo_id, transaction).await?;
+
+    if promo.mode != DBPromoMode::UNIQUE {
+        return Err(ApiError::InvalidInput(
+            "codes can only be added to UNIQUE promocodes".to_string(),
+        ));
+    }
+
+    if promo.status == DBPromoStatus::ARCHIVED {
+        return Err(ApiError::InvalidInput(
+            "codes can't be added to archived promocodes".to_string(),
+        ));
+    }
+
+    Ok(promo)
+}
+
+#[derive(Deserialize, Validate)]
+struct GetCodesQuery {
+    #[validate(range(min = 0))]
+    limit: Option<u32>,
+
+    #[validate(range(min = 0))]
+    offset: Option<u32>,
+
+    total: Option<bool>,
+}
+
+/// Lists the code pool of a UNIQUE promo in the order codes are issued in.
+#[get("/codes")]
+pub async fn get_handler(
+    req: HttpRequest,
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+    query: Query<GetCodesQuery>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::PromoRead)?;
+
+    let page = PageRequest::new(query.limit, query.offset, None, query.total)?;
+
+    let promo = get_company_promo(&actor, path.promo_id, &pool).await?;
+
+    if promo.mode != DBPromoMode::UNIQUE {
+        return Err(ApiError::InvalidInput(
+            "only UNIQUE promocodes have codes".to_string(),
+        ));
+    }
+
+    let codes = Page {
+        items: DBPromoCode::get_pageable(promo.id, &page, &**pool).await?,
+        total: page.with_total.then_some(promo.unique_count),
+        next_cursor: None,
+    };
+
+    Ok(codes.into_response(&req))
+}
+
+#[derive(Serialize, Debug)]
+struct AddCodesResponse {
+    added: u64,
+    skipped: u64,
+}
+
+/// Appends codes from a CSV or newline-delimited upload. Only the first
+/// column of every row is used; blank lines, duplicates and codes the promo
+/// already has are skipped.
+#[post("/codes")]
+pub async fn post_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+    mut payload: Payload,
+) -> Result<Json<AddCodesResponse>, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
+    // Read the whole upload before locking the promo, so a slow client can't
+    // hold the lock.
+    let mut upload = CodeUpload::default();
+    let mut buffer = Vec::new();
+
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk
+            .map_err(|err| ApiError::InvalidInput(format!("Failed to read the upload: {err}")))?;
+        buffer.extend_from_slice(&chunk);
+
+        let mut start = 0;
+        while let Some(end) = buffer[start..].iter().position(|&b| b == b'\n') {
+            upload.push_line(&buffer[start..start + end])?;
+            start += end + 1;
+        }
+        buffer.drain(..start);
+
+        if buffer.len() > MAX_LINE_LENGTH {
+            return Err(ApiError::InvalidInput(format!(
+                "line {} is too long",
+                upload.line + 1
+            )));
+        }
+    }
+
+    if !buffer.is_empty() {
+        upload.push_line(&buffer)?;
+    }
+    if upload.received == 0 {
+        return Err(ApiError::InvalidInput(
+            "the upload doesn't contain any codes".to_string(),
+        ));
+    }
+
+    let mut transaction = pool.begin().await?;
+    let promo = lock_unique_promo(&actor, path.promo_id, &mut transaction).await?;
+    let response = upload.insert(promo.id, &mut transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(response))
+}
+
+#[derive(Default)]
+struct CodeUpload {
+    line: usize,
+    seen: HashSet<String>,
+    codes: Vec<String>,
+    received: u64,
+}
+
+impl CodeUpload {
+    fn push_line(&mut self, line: &[u8]) -> Result<(), ApiError> {
+        self.line += 1;
+
+        let line = std::str::from_utf8(line).map_err(|_| {
+            ApiError::InvalidInput(format!("line {} is not valid UTF-8", self.line))
+        })?;
+        let Some(code) = parse_code_line(line) else {
+            return Ok(());
+        };
+        if self.line == 1 && code.eq_ignore_ascii_case("code") {
+            return Ok(());
+        }
+
+        if !validate_code(code) {
+            return Err(ApiError::InvalidInput(format!(
+                "line {}: codes must be 1 to {MAX_CODE_LENGTH} printable ASCII characters",
+                self.line
+            )));
+        }
+
+        self.received += 1;
+        if self.seen.contains(code) {
+            return Ok(());
+        }
+        if self.seen.len() >= MAX_CODES_PER_REQUEST {
+            return Err(ApiError::InvalidInput(format!(
+                "a single upload can't contain more than {MAX_CODES_PER_REQUEST} codes"
+            )));
+        }
+        self.seen.insert(code.to_string());
+        self.codes.push(code.to_string());
+
+        Ok(())
+    }
+
+    /// Appends the codes in upload order. Expects the promo to be locked.
+    async fn insert(
+        self,
+        promo_id: Uuid,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<AddCodesResponse, ApiError> {
+        let mut added = 0;
+        for batch in self.codes.chunks(INSERT_BATCH_SIZE) {
+            added += DBPromoCode::insert_many(promo_id, batch, transaction).await?;
+        }
+
+        Ok(AddCodesResponse {
+            added,
+            skipped: self.received - added,
+        })
+    }
+}
+
+#[derive(Deserialize, Validate, Debug)]
+pub(super) struct GenerateCodesRequest {
+    #[validate(range(min = 1, max = 1000000))]
+    count: usize,
+
+    #[validate(length(min = 2, max = 64))]
+    alphabet: Option<String>,
+
+    #[validate(range(min = 4, max = 30))]
+    length: Option<usize>,
+
+    #[validate(length(max = 20))]
+    prefix: Option<String>,
+
+    #[serde(default)]
+    check_digit: bool,
+}
+
+impl GenerateCodesRequest {
+    /// Checks the requested code format and builds a generator for it.
+    /// Expects the request to be validated already.
+    pub(super) fn generator(&self) -> Result<CodeGenerator, ApiError> {
+        let alphabet = self.alphabet.as_deref().unwrap_or(DEFAULT_CODE_ALPHABET);
+        if alphabet.chars().any(|c| !c.is_ascii_alphanumeric())
+            || alphabet.chars().collect::<HashSet<_>>().len() != alphabet.len()
+        {
+            return Err(ApiError::InvalidInput(
+                "field `alphabet` must consist of distinct letters and digits".to_string(),
+            ));
+        }
+
+        let prefix = self.prefix.as_deref().unwrap_or_default();
+        if !prefix.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(ApiError::InvalidInput(
+                "field `prefix` must consist of printable ASCII characters".to_string(),
+            ));
+        }
+
+        let generator = CodeGenerator::new(
+            alphabet,
+            self.length.unwrap_or(DEFAULT_CODE_LENGTH),
+            prefix,
+            self.check_digit,
+        );
+
+        if generator.code_length() > MAX_CODE_LENGTH {
+            return Err(ApiError::InvalidInput(format!(
+                "generated codes can't be longer than {MAX_CODE_LENGTH} characters"
+            )));
+        }
+
+        // Keep the code space sparse so that codes stay hard to guess and
+        // collisions with already issued ones remain rare.
+        if generator.capacity() < self.count as f64 * 1000.0 {
+            return Err(ApiError::InvalidInput(
+                "`alphabet` and `length` allow too few codes for the requested `count`".to_string(),
+            ));
+        }
+
+        Ok(generator)
+    }
+
+    /// Adds `count` freshly generated codes to the promo's pool.
+    pub(super) async fn generate(
+        &self,
+        promo_id: Uuid,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<u64, ApiError> {
+        let mut generator = self.generator()?;
+
+        let mut added = 0;
+        while (added as usize) < self.count {
+            let batch = (0..INSERT_BATCH_SIZE.min(self.count - added as usize))
+                .map(|_| generator.generate())
+                .collect::<Vec<_>>();
+
+            let inserted = DBPromoCode::insert_many(promo_id, &batch, transaction).await?;
+            if inserted == 0 {
+                return Err(ApiError::InvalidInput(
+                    "couldn't generate enough distinct codes, try a longer `length`".to_string(),
+                ));
+            }
+            added += inserted;
+        }
+
+        Ok(added)
+    }
+}
+
+#[post("/codes/generate")]
+pub async fn generate_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+    Json(body): Json<GenerateCodesRequest>,
+) -> Result<Json<AddCodesResponse>, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+    body.generator()?;
+
+    let mut transaction = pool.begin().await?;
+    let promo = lock_unique_promo(&actor, path.promo_id, &mut transaction).await?;
+
+    let added = body.generate(promo.id, &mut transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(AddCodesResponse { added, skipped: 0 }))
+}
@@ -0,0 +1,113 @@
+use actix_web::{
+    get, post,
+    web::{Data, Json, Path, ReqData},
+    HttpRequest, HttpResponse,
+};
+use actix_web_lab::extract::Query;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    database::models::{DBPromoRevision, DBPromoStatus},
+    models::{
+        CompanyActor, CompanyPermission, PageRequest, Promo, PromoPath, PromoRevision,
+        PromoSnapshot,
+    },
+    routes::ApiError,
+};
+
+use super::{get_company_promo, lock_company_promo};
+
+#[derive(Deserialize, Validate)]
+struct HistoryQuery {
+    #[validate(range(min = 0))]
+    limit: Option<u32>,
+
+    #[validate(range(min = 0))]
+    offset: Option<u32>,
+
+    cursor: Option<String>,
+
+    total: Option<bool>,
+}
+
+#[get("/history")]
+pub async fn get_handler(
+    req: HttpRequest,
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+    query: Query<HistoryQuery>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::PromoRead)?;
+
+    let page = PageRequest::new(
+        query.limit,
+        query.offset,
+        query.cursor.as_deref(),
+        query.total,
+    )?;
+
+    let promo = get_company_promo(&actor, path.promo_id, &pool).await?;
+
+    let revisions = PromoRevision::get_pageable(promo.id, &page, &**pool).await?;
+
+    Ok(revisions.into_response(&req))
+}
+
+#[derive(Deserialize, Debug)]
+struct RevisionPath {
+    promo_id: Uuid,
+    revision: i32,
+}
+
+/// Brings the editable fields back to how they were after `revision`. The
+/// status is left as is, it only changes through the lifecycle endpoints.
+#[post("/history/{revision}/restore")]
+pub async fn restore_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<RevisionPath>,
+) -> Result<Json<Promo>, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
+    let mut transaction = pool.begin().await?;
+
+    let promo = lock_company_promo(&actor, path.promo_id, &mut transaction).await?;
+
+    if promo.status == DBPromoStatus::ARCHIVED {
+        return Err(ApiError::InvalidInput(
+            "archived promos can't be restored".to_string(),
+        ));
+    }
+
+    let snapshot = DBPromoRevision::get_by_revision(promo.id, path.revision, &mut *transaction)
+        .await?
+        .ok_or(ApiError::NotFound)?
+        .into_model()
+        .snapshot()?;
+
+    if promo.used_count > snapshot.max_count {
+        return Err(ApiError::InvalidInput(
+            "field `max_count` must be greater than current number of activations".to_string(),
+        ));
+    }
+
+    let before = PromoSnapshot::from(&promo);
+    let promo = promo.restore(snapshot, &mut transaction).await?;
+
+    PromoRevision::record(
+        &actor,
+        Some(&before),
+        &promo,
+        Some(path.revision),
+        &mut transaction,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(promo.into_model()))
+}
@@ -1,25 +1,29 @@
 use actix_web::{
-    get,
+    delete, get,
     middleware::from_fn,
     patch,
     web::{scope, Data, Json, Path, ReqData, ServiceConfig},
 };
-use chrono::{DateTime, Utc};
-use serde::Deserialize;
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     auth::auth_middleware_cmp,
     database::models::{DBPromo, DBPromoMode},
-    models::{Promo, PromoPath, PromoTarget, Token},
-    routes::ApiError,
-    util::{
-        convertions::promo_date_format, cors::default_cors, validate::validation_errors_to_string,
+    models::{
+        CompanyActor, CompanyPermission, EmptyResponse, Promo, PromoPatch, PromoPath,
+        PromoRevision, PromoSnapshot,
     },
+    routes::ApiError,
+    util::{cors::default_cors, validate::validation_errors_to_string},
 };
 
+mod clone;
+mod codes;
+mod history;
 mod stat;
+mod status;
 
 pub fn config(cfg: &mut ServiceConfig) {
     cfg.service(
@@ -28,59 +32,92 @@ pub fn config(cfg: &mut ServiceConfig) {
             .wrap(from_fn(auth_middleware_cmp))
             .service(get_handler)
             .service(patch_handler)
-            .service(stat::get_handler),
+            .service(delete_handler)
+            .service(history::get_handler)
+            .service(history::restore_handler)
+            .service(status::publish_handler)
+            .service(status::pause_handler)
+            .service(status::archive_handler)
+            .service(clone::post_handler)
+            .service(codes::get_handler)
+            .service(codes::post_handler)
+            .service(codes::generate_handler)
+            .service(stat::get_handler)
+            .service(stat::timeseries_handler),
     );
 }
 
-#[get("")]
-pub async fn get_handler(
-    pool: Data<PgPool>,
-    token: ReqData<Token>,
-    path: Path<PromoPath>,
-) -> Result<Json<Promo>, ApiError> {
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+async fn get_company_promo(
+    actor: &CompanyActor,
+    promo_id: Uuid,
+    pool: &PgPool,
+) -> Result<DBPromo, ApiError> {
+    let promo = if let Some(promo) = DBPromo::get_by_id(promo_id, pool).await? {
         promo
     } else {
         return Err(ApiError::NotFound);
     };
 
-    let company = token.get_company(&**pool).await?;
-
-    if promo.company_id != company.id {
+    if promo.company_id != actor.company_id {
         return Err(ApiError::NotOwner);
     }
 
-    Ok(Json(promo.into_model()))
+    Ok(promo)
 }
 
-#[derive(Deserialize, Validate, Debug)]
-struct EditPromoRequest {
-    #[validate(length(min = 10, max = 300))]
-    description: Option<String>,
+/// Locks the company's promo for the rest of the transaction and reads it
+/// afterwards, so concurrent edits are diffed against and numbered after each
+/// other's revisions.
+async fn lock_company_promo(
+    actor: &CompanyActor,
+    promo_id: Uuid,
+    transaction: &mut Transaction<'_, Postgres>,
+) -> Result<DBPromo, ApiError> {
+    if !DBPromo::lock(promo_id, transaction).await? {
+        return Err(ApiError::NotFound);
+    }
+
+    let promo = DBPromo::get_by_id(promo_id, &mut **transaction)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+
+    if promo.company_id != actor.company_id {
+        return Err(ApiError::NotOwner);
+    }
 
-    #[validate(length(max = 350))]
-    image_url: Option<String>,
+    Ok(promo)
+}
 
-    #[validate(nested)]
-    target: Option<PromoTarget>,
+#[get("")]
+pub async fn get_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+) -> Result<Json<Promo>, ApiError> {
+    actor.require(CompanyPermission::PromoRead)?;
 
-    #[validate(range(min = 0, max = 100000000))]
-    max_count: Option<i32>,
+    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+        promo
+    } else {
+        return Err(ApiError::NotFound);
+    };
 
-    #[serde(default, with = "promo_date_format")]
-    active_from: Option<DateTime<Utc>>,
+    if promo.company_id != actor.company_id {
+        return Err(ApiError::NotOwner);
+    }
 
-    #[serde(default, with = "promo_date_format")]
-    active_until: Option<DateTime<Utc>>,
+    Ok(Json(promo.into_model()))
 }
 
 #[patch("")]
 pub async fn patch_handler(
     pool: Data<PgPool>,
-    token: ReqData<Token>,
+    actor: ReqData<CompanyActor>,
     path: Path<PromoPath>,
-    body: Json<EditPromoRequest>,
+    body: Json<PromoPatch>,
 ) -> Result<Json<Promo>, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
     body.validate()
         .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
 
@@ -93,11 +130,9 @@ pub async fn patch_handler(
         ));
     }
 
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
-        promo
-    } else {
-        return Err(ApiError::NotFound);
-    };
+    let mut transaction = pool.begin().await?;
+
+    let promo = lock_company_promo(&actor, path.promo_id, &mut transaction).await?;
 
     if let Some(max_count) = body.max_count {
         if promo.mode == DBPromoMode::UNIQUE && max_count != 1 {
@@ -112,28 +147,47 @@ pub async fn patch_handler(
         }
     }
 
-    let company = token.get_company(&**pool).await?;
-
-    if promo.company_id != company.id {
-        return Err(ApiError::NotOwner);
+    if promo.mode == DBPromoMode::UNIQUE
+        && (body.per_user_limit.is_some() || body.cooldown.is_some() || body.max_per_day.is_some())
+    {
+        return Err(ApiError::InvalidInput(
+            "activation limits can only be used in COMMON promocodes".to_string(),
+        ));
     }
 
+    let before = PromoSnapshot::from(&promo);
+    let promo = promo.patch(&body, &mut transaction).await?;
+
+    PromoRevision::record(&actor, Some(&before), &promo, None, &mut transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(promo.into_model()))
+}
+
+/// Only promos that have never been activated can be deleted, the rest
+/// have to be archived to keep users' activation history intact.
+#[delete("")]
+pub async fn delete_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+) -> Result<EmptyResponse, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
     let mut transaction = pool.begin().await?;
 
-    let promo = promo
-        .patch(
-            body.description.clone(),
-            body.image_url.clone(),
-            body.target.clone().map(PromoTarget::into_db),
-            body.max_count,
-            body.active_from,
-            body.active_until,
-            &mut transaction,
-        )
-        .await?
-        .into_model();
+    let promo = lock_company_promo(&actor, path.promo_id, &mut transaction).await?;
+
+    if promo.used_count > 0 {
+        return Err(ApiError::InvalidInput(
+            "promos with activations can only be archived".to_string(),
+        ));
+    }
+
+    promo.delete(&mut transaction).await?;
 
     transaction.commit().await?;
 
-    Ok(Json(promo))
+    Ok(EmptyResponse::default())
 }
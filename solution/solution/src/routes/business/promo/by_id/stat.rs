@@ -2,33 +2,82 @@ use actix_web::{
     get,
     web::{Data, Json, Path, ReqData},
 };
+use actix_web_lab::extract::Query;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
 use sqlx::PgPool;
 
 use crate::{
-    database::models::DBPromo,
-    models::{PromoPath, PromoStats, Token},
+    models::{
+        CompanyActor, CompanyPermission, PromoPath, PromoStats, PromoTimeseries, StatsBucket,
+        StatsSplit,
+    },
     routes::ApiError,
 };
 
+use super::get_company_promo;
+
+const DEFAULT_TIMESERIES_POINTS: i32 = 30;
+const MAX_TIMESERIES_POINTS: i64 = 1000;
+
 #[get("/stat")]
 pub async fn get_handler(
     pool: Data<PgPool>,
-    token: ReqData<Token>,
+    actor: ReqData<CompanyActor>,
     path: Path<PromoPath>,
 ) -> Result<Json<PromoStats>, ApiError> {
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
-        promo
-    } else {
-        return Err(ApiError::NotFound);
-    };
-
-    let company = token.get_company(&**pool).await?;
+    actor.require(CompanyPermission::StatsRead)?;
 
-    if promo.company_id != company.id {
-        return Err(ApiError::NotOwner);
-    }
+    let promo = get_company_promo(&actor, path.promo_id, &pool).await?;
 
     let stats = PromoStats::get(promo.id, &**pool).await?;
 
     Ok(Json(stats))
 }
+
+#[derive(Deserialize)]
+struct TimeseriesQuery {
+    #[serde(default)]
+    bucket: StatsBucket,
+
+    from: Option<DateTime<Utc>>,
+
+    to: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    split_by: Vec<StatsSplit>,
+}
+
+#[get("/stat/timeseries")]
+pub async fn timeseries_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+    query: Query<TimeseriesQuery>,
+) -> Result<Json<PromoTimeseries>, ApiError> {
+    actor.require(CompanyPermission::StatsRead)?;
+
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query
+        .from
+        .unwrap_or_else(|| to - query.bucket.duration() * DEFAULT_TIMESERIES_POINTS);
+
+    if from >= to {
+        return Err(ApiError::InvalidInput(
+            "`from` must be less than `to`".to_string(),
+        ));
+    }
+
+    if (to - from).num_seconds() / query.bucket.duration().num_seconds() >= MAX_TIMESERIES_POINTS {
+        return Err(ApiError::InvalidInput(format!(
+            "the requested range can't span more than {MAX_TIMESERIES_POINTS} buckets"
+        )));
+    }
+
+    let promo = get_company_promo(&actor, path.promo_id, &pool).await?;
+
+    let timeseries =
+        PromoTimeseries::get(promo.id, query.bucket, from, to, &query.split_by, &**pool).await?;
+
+    Ok(Json(timeseries))
+}
@@ -0,0 +1,95 @@
+use actix_web::{
+    post,
+    web::{Data, Json, Path, ReqData},
+};
+use sqlx::PgPool;
+
+use crate::{
+    database::models::{DBPromo, DBPromoMode, DBPromoStatus},
+    models::{CompanyActor, CompanyPermission, Promo, PromoPath, PromoRevision, PromoSnapshot},
+    routes::ApiError,
+};
+
+use super::lock_company_promo;
+
+async fn transition(
+    actor: &CompanyActor,
+    path: &PromoPath,
+    status: DBPromoStatus,
+    pool: &PgPool,
+) -> Result<Promo, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
+    let mut transaction = pool.begin().await?;
+
+    let promo = lock_company_promo(actor, path.promo_id, &mut transaction).await?;
+
+    if !promo.status.can_become(status) {
+        return Err(ApiError::InvalidStatusTransition(promo.status, status));
+    }
+
+    // Drafts and clones can be created without codes.
+    if status == DBPromoStatus::PUBLISHED
+        && promo.mode == DBPromoMode::UNIQUE
+        && promo.unique_available == 0
+    {
+        return Err(ApiError::InvalidInput(
+            "add codes to the promo before publishing it".to_string(),
+        ));
+    }
+
+    if !promo.set_status(status, &mut transaction).await? {
+        return Err(ApiError::InvalidStatusTransition(promo.status, status));
+    }
+
+    let updated = DBPromo::get_by_id(promo.id, &mut *transaction)
+        .await?
+        .ok_or(ApiError::NotFound)?;
+    PromoRevision::record(
+        actor,
+        Some(&PromoSnapshot::from(&promo)),
+        &updated,
+        None,
+        &mut transaction,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(updated.into_model())
+}
+
+/// Makes a draft visible to users or resumes a paused promo.
+#[post("/publish")]
+pub async fn publish_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+) -> Result<Json<Promo>, ApiError> {
+    transition(&actor, &path, DBPromoStatus::PUBLISHED, &pool)
+        .await
+        .map(Json)
+}
+
+#[post("/pause")]
+pub async fn pause_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+) -> Result<Json<Promo>, ApiError> {
+    transition(&actor, &path, DBPromoStatus::PAUSED, &pool)
+        .await
+        .map(Json)
+}
+
+/// Archived promos can't be activated or published again.
+#[post("/archive")]
+pub async fn archive_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<PromoPath>,
+) -> Result<Json<Promo>, ApiError> {
+    transition(&actor, &path, DBPromoStatus::ARCHIVED, &pool)
+        .await
+        .map(Json)
+}
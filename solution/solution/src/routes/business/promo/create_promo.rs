@@ -5,128 +5,186 @@ use actix_web::{
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    database::models::DBPromoMode,
-    models::{Promo, PromoTarget, Token},
+    database::models::{DBPromo, DBPromoMode, DBPromoStatus},
+    models::{CompanyActor, CompanyPermission, Promo, PromoRevision, PromoTarget},
     routes::ApiError,
     util::{convertions::promo_date_format, validate::validation_errors_to_string},
 };
 
 #[derive(Deserialize, Validate, Debug)]
-struct CreatePromoRequest {
+pub(super) struct CreatePromoRequest {
     #[validate(length(min = 10, max = 300))]
-    description: String,
+    pub(super) description: String,
 
     #[validate(url, length(max = 350))]
-    image_url: Option<String>,
+    pub(super) image_url: Option<String>,
 
     #[validate(nested)]
-    target: PromoTarget,
+    pub(super) target: PromoTarget,
 
     #[validate(range(min = 0, max = 100000000))]
-    max_count: i32,
+    pub(super) max_count: i32,
 
     #[serde(default, with = "promo_date_format")]
-    active_from: Option<DateTime<Utc>>,
+    pub(super) active_from: Option<DateTime<Utc>>,
 
     #[serde(default, with = "promo_date_format")]
-    active_until: Option<DateTime<Utc>>,
+    pub(super) active_until: Option<DateTime<Utc>>,
 
-    mode: DBPromoMode,
+    #[validate(range(min = 1, max = 100000000))]
+    pub(super) per_user_limit: Option<i32>,
+
+    #[validate(range(min = 1, max = 31536000))]
+    pub(super) cooldown: Option<i32>,
+
+    #[validate(range(min = 1, max = 100000000))]
+    pub(super) max_per_day: Option<i32>,
+
+    pub(super) mode: DBPromoMode,
 
     #[validate(length(min = 5, max = 30))]
-    promo_common: Option<String>,
+    pub(super) promo_common: Option<String>,
 
     #[validate(length(min = 1, max = 5000))]
-    promo_unique: Option<Vec<String>>,
+    pub(super) promo_unique: Option<Vec<String>>,
+
+    /// New promos are published right away unless created as drafts.
+    pub(super) status: Option<DBPromoStatus>,
+}
+
+impl CreatePromoRequest {
+    pub(super) fn check(&self) -> Result<(), ApiError> {
+        self.validate()
+            .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+        if self.active_from.is_some()
+            && self.active_until.is_some()
+            && self.active_from.unwrap() >= self.active_until.unwrap()
+        {
+            return Err(ApiError::InvalidInput(
+                "`active_from` must be less than `active_until`".to_string(),
+            ));
+        }
+
+        match self {
+            CreatePromoRequest {
+                mode: DBPromoMode::COMMON,
+                promo_common: None,
+                ..
+            } => Err(ApiError::InvalidInput(
+                "field `promo_common` is required for COMMON promocodes".to_string(),
+            )),
+            CreatePromoRequest {
+                status: Some(DBPromoStatus::PAUSED | DBPromoStatus::ARCHIVED),
+                ..
+            } => Err(ApiError::InvalidInput(
+                "field `status` must be either DRAFT or PUBLISHED".to_string(),
+            )),
+            CreatePromoRequest {
+                mode: DBPromoMode::UNIQUE,
+                promo_unique: None,
+                status: None | Some(DBPromoStatus::PUBLISHED),
+                ..
+            } => Err(ApiError::InvalidInput(
+                "field `promo_unique` is required for published UNIQUE promocodes".to_string(),
+            )),
+            CreatePromoRequest {
+                mode: DBPromoMode::COMMON,
+                promo_unique: Some(..),
+                ..
+            } => Err(ApiError::InvalidInput(
+                "field `promo_unique` can't be used in COMMON promocodes".to_string(),
+            )),
+            CreatePromoRequest {
+                mode: DBPromoMode::UNIQUE,
+                promo_common: Some(..),
+                ..
+            } => Err(ApiError::InvalidInput(
+                "field `promo_common` can't be used in UNIQUE promocodes".to_string(),
+            )),
+            CreatePromoRequest {
+                mode: DBPromoMode::UNIQUE,
+                max_count: 0 | 2..,
+                ..
+            } => Err(ApiError::InvalidInput(
+                "field `max_count` must be 1 for UNIQUE promocodes".to_string(),
+            )),
+            CreatePromoRequest {
+                mode: DBPromoMode::UNIQUE,
+                ..
+            } if self.per_user_limit.is_some()
+                || self.cooldown.is_some()
+                || self.max_per_day.is_some() =>
+            {
+                Err(ApiError::InvalidInput(
+                    "activation limits can only be used in COMMON promocodes".to_string(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) async fn insert(
+        self,
+        actor: &CompanyActor,
+        transaction: &mut Transaction<'_, Postgres>,
+    ) -> Result<DBPromo, ApiError> {
+        let company = actor.get_company(&mut **transaction).await?;
+
+        let promo = Promo {
+            id: Uuid::now_v7(),
+            company_id: company.id,
+            company_name: company.name,
+            description: self.description,
+            image_url: self.image_url,
+            target: self.target,
+            max_count: self.max_count,
+            active_from: self.active_from,
+            active_until: self.active_until,
+            mode: self.mode,
+            promo_common: self.promo_common,
+            promo_unique_count: None,
+            promo_unique_available: None,
+            like_count: 0,
+            used_count: 0,
+            comment_count: 0,
+            active: true,
+            per_user_limit: self.per_user_limit,
+            cooldown: self.cooldown,
+            max_per_day: self.max_per_day,
+            status: self.status.unwrap_or(DBPromoStatus::PUBLISHED),
+        }
+        .into_db()
+        .insert(
+            self.promo_unique.as_deref().unwrap_or_default(),
+            transaction,
+        )
+        .await?;
+
+        PromoRevision::record(actor, None, &promo, None, transaction).await?;
+
+        Ok(promo)
+    }
 }
 
 #[post("")]
 pub async fn post_handler(
     pool: Data<PgPool>,
-    token: ReqData<Token>,
+    actor: ReqData<CompanyActor>,
     Json(body): Json<CreatePromoRequest>,
 ) -> Result<HttpResponse, ApiError> {
-    body.validate()
-        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
-
-    if body.active_from.is_some()
-        && body.active_until.is_some()
-        && body.active_from.unwrap() >= body.active_until.unwrap()
-    {
-        return Err(ApiError::InvalidInput(
-            "`active_from` must be less than `active_until`".to_string(),
-        ));
-    }
+    actor.require(CompanyPermission::PromoWrite)?;
 
-    match body {
-        CreatePromoRequest {
-            mode: DBPromoMode::COMMON,
-            promo_common: None,
-            ..
-        } => Err(ApiError::InvalidInput(
-            "field `promo_common` is required for COMMON promocodes".to_string(),
-        )),
-        CreatePromoRequest {
-            mode: DBPromoMode::UNIQUE,
-            promo_unique: None,
-            ..
-        } => Err(ApiError::InvalidInput(
-            "field `promo_unique` is required for UNIQUE promocodes".to_string(),
-        )),
-        CreatePromoRequest {
-            mode: DBPromoMode::COMMON,
-            promo_unique: Some(..),
-            ..
-        } => Err(ApiError::InvalidInput(
-            "field `promo_unique` can't be used in COMMON promocodes".to_string(),
-        )),
-        CreatePromoRequest {
-            mode: DBPromoMode::UNIQUE,
-            promo_common: Some(..),
-            ..
-        } => Err(ApiError::InvalidInput(
-            "field `promo_common` can't be used in UNIQUE promocodes".to_string(),
-        )),
-        CreatePromoRequest {
-            mode: DBPromoMode::UNIQUE,
-            max_count: 0 | 2..,
-            ..
-        } => Err(ApiError::InvalidInput(
-            "field `max_count` must be 1 for UNIQUE promocodes".to_string(),
-        )),
-        _ => Ok(()),
-    }?;
-
-    let company = token.get_company(&**pool).await?;
+    body.check()?;
 
     let mut transaction = pool.begin().await?;
 
-    let promo = Promo {
-        id: Uuid::now_v7(),
-        company_id: company.id,
-        company_name: company.name,
-        description: body.description.clone(),
-        image_url: body.image_url.clone(),
-        target: body.target,
-        max_count: body.max_count,
-        active_from: body.active_from,
-        active_until: body.active_until,
-        mode: body.mode,
-        promo_common: body.promo_common,
-        promo_unique: body.promo_unique,
-        like_count: 0,
-        used_count: 0,
-        comment_count: 0,
-        active: true,
-    }
-    .into_db()
-    .insert(&mut transaction)
-    .await?;
+    let promo = body.insert(&actor, &mut transaction).await?;
 
     transaction.commit().await?;
 
@@ -134,6 +192,6 @@ pub async fn post_handler(
 }
 
 #[derive(Serialize, Debug)]
-struct PostPromoResponse {
-    id: Uuid,
+pub(super) struct PostPromoResponse {
+    pub(super) id: Uuid,
 }
@@ -1,15 +1,17 @@
 use actix_web::{
     get,
     web::{Data, ReqData},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 use actix_web_lab::extract::Query;
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use sqlx::PgPool;
 use validator::Validate;
 
 use crate::{
-    models::{Promo, SortPromosBy, Token},
+    database::models::{DBPromoMode, DBPromoQuery, DBPromoSortField, DBPromoStatus, DBSortOrder},
+    models::{CompanyActor, CompanyPermission, PageRequest, Promo, SortPromosBy},
     routes::ApiError,
     util::validate::validate_countries,
 };
@@ -24,27 +26,58 @@ struct ListPromosQuery {
 
     sort_by: Option<SortPromosBy>,
 
+    #[serde(default)]
+    order: DBSortOrder,
+
     #[validate(custom(function = "validate_countries"))]
     country: Option<Vec<String>>,
+
+    active: Option<bool>,
+
+    mode: Option<DBPromoMode>,
+
+    status: Option<DBPromoStatus>,
+
+    from: Option<DateTime<Utc>>,
+
+    to: Option<DateTime<Utc>>,
+
+    cursor: Option<String>,
+
+    total: Option<bool>,
 }
 
 #[get("")]
 pub async fn get_handler(
+    req: HttpRequest,
     pool: Data<PgPool>,
-    token: ReqData<Token>,
+    actor: ReqData<CompanyActor>,
     query: Query<ListPromosQuery>,
 ) -> Result<HttpResponse, ApiError> {
-    let (promos, count) = Promo::get_pageable(
-        token.entity,
+    actor.require(CompanyPermission::PromoRead)?;
+
+    let page = PageRequest::new(
         query.limit,
         query.offset,
-        &query.sort_by,
-        &query.country,
-        &**pool,
-    )
-    .await?;
-
-    Ok(HttpResponse::Ok()
-        .insert_header(("X-Total-Count", count))
-        .json(promos))
+        query.cursor.as_deref(),
+        query.total,
+    )?;
+
+    let filter = DBPromoQuery::new()
+        .company(actor.company_id)
+        .countries(query.country.clone())
+        .active(query.active)
+        .mode(query.mode)
+        .status(query.status)
+        .active_between(query.from, query.to)
+        .sort(
+            query
+                .sort_by
+                .map_or(DBPromoSortField::Id, SortPromosBy::into_db),
+            query.order,
+        );
+
+    let promos = Promo::get_pageable(&filter, &page, &**pool).await?;
+
+    Ok(promos.into_response(&req))
 }
@@ -0,0 +1,98 @@
+use actix_web::{
+    middleware::from_fn,
+    post,
+    web::{scope, Data, Json, ReqData, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::auth_middleware_cmp,
+    database::models::DBPromoActivation,
+    models::{CompanyActor, CompanyPermission},
+    routes::ApiError,
+    util::{cors::default_cors, validate::validation_errors_to_string},
+};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("redeem")
+            .wrap(default_cors())
+            .wrap(from_fn(auth_middleware_cmp))
+            .service(post_handler),
+    );
+}
+
+#[derive(Deserialize, Validate, Debug)]
+struct RedeemRequest {
+    #[validate(length(min = 1, max = 30))]
+    code: String,
+
+    /// Needed when the code is issued in more than one of the company's promos.
+    promo_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Debug)]
+struct RedeemResponse {
+    promo_id: Uuid,
+    user_id: Uuid,
+    code: String,
+    activated_at: DateTime<Utc>,
+    redeemed_at: DateTime<Utc>,
+}
+
+#[post("")]
+async fn post_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    Json(body): Json<RedeemRequest>,
+) -> Result<Json<RedeemResponse>, ApiError> {
+    actor.require(CompanyPermission::PromoWrite)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+    let mut transaction = pool.begin().await?;
+
+    let activations = DBPromoActivation::get_by_code(
+        actor.company_id,
+        &body.code,
+        body.promo_id,
+        &mut *transaction,
+    )
+    .await?;
+    let activation = match activations.as_slice() {
+        [] => return Err(ApiError::NotFound),
+        [activation] => activation,
+        _ => {
+            return Err(ApiError::InvalidInput(
+                "the code is issued in several promos, specify `promo_id`".to_string(),
+            ))
+        }
+    };
+
+    let redeemed_at = Utc::now();
+    let Some(activation) = DBPromoActivation::redeem(
+        activation.promo_id,
+        &activation.promo,
+        redeemed_at,
+        &mut transaction,
+    )
+    .await?
+    else {
+        return Err(ApiError::AlreadyRedeemed);
+    };
+
+    transaction.commit().await?;
+
+    Ok(Json(RedeemResponse {
+        promo_id: activation.promo_id,
+        user_id: activation.user_id,
+        code: activation.promo,
+        activated_at: activation.date,
+        redeemed_at,
+    }))
+}
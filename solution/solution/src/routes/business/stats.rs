@@ -0,0 +1,34 @@
+use actix_web::{
+    get,
+    middleware::from_fn,
+    web::{scope, Data, Json, ReqData, ServiceConfig},
+};
+use sqlx::PgPool;
+
+use crate::{
+    auth::auth_middleware_cmp,
+    models::{CompanyActor, CompanyPermission, CompanyStats},
+    routes::ApiError,
+    util::cors::default_cors,
+};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("stats")
+            .wrap(default_cors())
+            .wrap(from_fn(auth_middleware_cmp))
+            .service(get_handler),
+    );
+}
+
+#[get("")]
+async fn get_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+) -> Result<Json<CompanyStats>, ApiError> {
+    actor.require(CompanyPermission::StatsRead)?;
+
+    let stats = CompanyStats::get(actor.company_id, &**pool).await?;
+
+    Ok(Json(stats))
+}
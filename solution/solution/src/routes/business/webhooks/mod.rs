@@ -0,0 +1,246 @@
+use actix_web::{
+    delete, get,
+    middleware::from_fn,
+    patch, post,
+    web::{scope, Data, Json, Path, Query, ReqData, ServiceConfig},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    auth::auth_middleware_cmp,
+    database::models::{DBWebhook, DBWebhookDelivery},
+    models::{
+        CompanyActor, CompanyPermission, Cursor, EmptyResponse, Page, PageRequest, Webhook,
+        WebhookDelivery, WebhookEvent, WebhookPath,
+    },
+    routes::ApiError,
+    util::{cors::default_cors, validate::validation_errors_to_string, webhooks},
+};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("webhooks")
+            .wrap(default_cors())
+            .wrap(from_fn(auth_middleware_cmp))
+            .service(get_all_handler)
+            .service(post_handler)
+            .service(get_handler)
+            .service(patch_handler)
+            .service(delete_handler)
+            .service(get_deliveries_handler),
+    );
+}
+
+async fn get_company_webhook(
+    actor: &CompanyActor,
+    webhook_id: Uuid,
+    pool: &PgPool,
+) -> Result<DBWebhook, ApiError> {
+    match DBWebhook::get_by_id(webhook_id, pool).await? {
+        Some(webhook) if webhook.company_id == actor.company_id => Ok(webhook),
+        _ => Err(ApiError::NotFound),
+    }
+}
+
+fn events_into_db(events: &[WebhookEvent]) -> Vec<String> {
+    let mut events: Vec<String> = events.iter().map(WebhookEvent::to_string).collect();
+    events.sort();
+    events.dedup();
+    events
+}
+
+#[get("")]
+async fn get_all_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+) -> Result<Json<Vec<Webhook>>, ApiError> {
+    actor.require(CompanyPermission::WebhooksManage)?;
+
+    let webhooks = DBWebhook::get_all(actor.company_id, &**pool)
+        .await?
+        .into_iter()
+        .map(DBWebhook::into_model)
+        .collect();
+
+    Ok(Json(webhooks))
+}
+
+#[derive(Deserialize, Validate, Debug)]
+struct CreateWebhookRequest {
+    #[validate(url, length(max = 2048))]
+    url: String,
+
+    #[validate(length(min = 1))]
+    events: Vec<WebhookEvent>,
+}
+
+#[post("")]
+async fn post_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    body: Json<CreateWebhookRequest>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::WebhooksManage)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+    webhooks::check_destination(&body.url)
+        .await
+        .map_err(ApiError::InvalidInput)?;
+
+    let mut transaction = pool.begin().await?;
+
+    let webhook = DBWebhook {
+        id: Uuid::now_v7(),
+        company_id: actor.company_id,
+        url: body.url.clone(),
+        secret: Webhook::generate_secret(),
+        events: events_into_db(&body.events),
+        active: true,
+        created_at: Utc::now(),
+    }
+    .insert(&mut transaction)
+    .await?
+    .into_model();
+
+    transaction.commit().await?;
+
+    Ok(HttpResponse::Created().json(CreateWebhookResponse {
+        secret: webhook.secret.clone(),
+        webhook,
+    }))
+}
+
+#[derive(Serialize, Debug)]
+struct CreateWebhookResponse {
+    secret: String,
+
+    #[serde(flatten)]
+    webhook: Webhook,
+}
+
+#[get("/{webhook_id}")]
+async fn get_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<WebhookPath>,
+) -> Result<Json<Webhook>, ApiError> {
+    actor.require(CompanyPermission::WebhooksManage)?;
+
+    let webhook = get_company_webhook(&actor, path.webhook_id, &pool).await?;
+
+    Ok(Json(webhook.into_model()))
+}
+
+#[derive(Deserialize, Validate, Debug)]
+struct EditWebhookRequest {
+    #[validate(url, length(max = 2048))]
+    url: Option<String>,
+
+    #[validate(length(min = 1))]
+    events: Option<Vec<WebhookEvent>>,
+
+    active: Option<bool>,
+}
+
+#[patch("/{webhook_id}")]
+async fn patch_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<WebhookPath>,
+    body: Json<EditWebhookRequest>,
+) -> Result<Json<Webhook>, ApiError> {
+    actor.require(CompanyPermission::WebhooksManage)?;
+
+    body.validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+    if let Some(url) = &body.url {
+        webhooks::check_destination(url)
+            .await
+            .map_err(ApiError::InvalidInput)?;
+    }
+
+    let webhook = get_company_webhook(&actor, path.webhook_id, &pool).await?;
+
+    let mut transaction = pool.begin().await?;
+
+    let webhook = webhook
+        .patch(
+            body.url.clone(),
+            body.events.as_deref().map(events_into_db),
+            body.active,
+            &mut transaction,
+        )
+        .await?;
+
+    transaction.commit().await?;
+
+    Ok(Json(webhook.into_model()))
+}
+
+#[delete("/{webhook_id}")]
+async fn delete_handler(
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<WebhookPath>,
+) -> Result<EmptyResponse, ApiError> {
+    actor.require(CompanyPermission::WebhooksManage)?;
+
+    let webhook = get_company_webhook(&actor, path.webhook_id, &pool).await?;
+
+    let mut transaction = pool.begin().await?;
+
+    webhook.delete(&mut transaction).await?;
+
+    transaction.commit().await?;
+
+    Ok(EmptyResponse::default())
+}
+
+#[derive(Deserialize, Validate)]
+struct GetDeliveriesQuery {
+    #[validate(range(min = 0))]
+    limit: Option<u32>,
+
+    #[validate(range(min = 0))]
+    offset: Option<u32>,
+
+    cursor: Option<String>,
+
+    total: Option<bool>,
+}
+
+#[get("/{webhook_id}/deliveries")]
+async fn get_deliveries_handler(
+    req: HttpRequest,
+    pool: Data<PgPool>,
+    actor: ReqData<CompanyActor>,
+    path: Path<WebhookPath>,
+    query: Query<GetDeliveriesQuery>,
+) -> Result<HttpResponse, ApiError> {
+    actor.require(CompanyPermission::WebhooksManage)?;
+
+    let page = PageRequest::new(
+        query.limit,
+        query.offset,
+        query.cursor.as_deref(),
+        query.total,
+    )?;
+
+    let webhook = get_company_webhook(&actor, path.webhook_id, &pool).await?;
+
+    let (deliveries, count) = DBWebhookDelivery::get_pageable(webhook.id, &page, &**pool).await?;
+
+    let deliveries: Page<WebhookDelivery> =
+        Page::new(deliveries, count, &page, |delivery: &DBWebhookDelivery| {
+            Cursor::with_date(delivery.created_at, delivery.id)
+        })
+        .map(DBWebhookDelivery::into_model);
+
+    Ok(deliveries.into_response(&req))
+}
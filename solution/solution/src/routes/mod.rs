@@ -9,7 +9,11 @@ mod not_found;
 mod ping;
 mod user;
 
-use crate::{auth::AuthenticationError, database::models::DatabaseError, util::cors::default_cors};
+use crate::{
+    auth::AuthenticationError,
+    database::models::{DBPromoStatus, DatabaseError},
+    util::cors::default_cors,
+};
 
 pub use self::not_found::not_found;
 
@@ -43,6 +47,9 @@ pub enum ApiError {
     #[error("You're not allowed to do this")]
     NotOwner,
 
+    #[error("Your role doesn't allow you to do this")]
+    InsufficientPermissions,
+
     #[error("You're not allowed to use this promo")]
     FraudDetected,
 
@@ -52,6 +59,24 @@ pub enum ApiError {
     #[error("You can't use this promo")]
     NotPromoTarget,
 
+    #[error("You've reached the activation limit for this promo")]
+    ActivationLimitReached,
+
+    #[error("You've activated this promo too recently, try again in {0}s")]
+    ActivationCooldown(i64),
+
+    #[error("This promo has run out of activations for today")]
+    DailyLimitReached,
+
+    #[error("A request with this idempotency key is still in progress")]
+    IdempotencyConflict,
+
+    #[error("This code has already been redeemed")]
+    AlreadyRedeemed,
+
+    #[error("A {0:?} promo can't become {1:?}")]
+    InvalidStatusTransition(DBPromoStatus, DBPromoStatus),
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -73,9 +98,16 @@ impl ApiError {
                 Self::Database(..) => "database_error",
                 Self::Authentication(err) => err.error_name(),
                 Self::NotOwner => "not_owner",
+                Self::InsufficientPermissions => "insufficient_permissions",
                 Self::FraudDetected => "fraud_suspence",
                 Self::PromoExpired => "promo_expired",
                 Self::NotPromoTarget => "not_promo_target",
+                Self::ActivationLimitReached => "activation_limit_reached",
+                Self::ActivationCooldown(..) => "activation_cooldown",
+                Self::DailyLimitReached => "daily_limit_reached",
+                Self::IdempotencyConflict => "idempotency_conflict",
+                Self::AlreadyRedeemed => "already_redeemed",
+                Self::InvalidStatusTransition(..) => "invalid_status_transition",
                 Self::Json(..) => "json_error",
                 Self::NotFound => "not_found",
                 Self::InvalidInput(..) => "invalid_input",
@@ -95,9 +127,16 @@ impl ResponseError for ApiError {
             Self::SqlxDatabase(..) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Authentication(err) => err.status_code(),
             Self::NotOwner => StatusCode::FORBIDDEN,
+            Self::InsufficientPermissions => StatusCode::FORBIDDEN,
             Self::FraudDetected => StatusCode::FORBIDDEN,
             Self::PromoExpired => StatusCode::FORBIDDEN,
             Self::NotPromoTarget => StatusCode::FORBIDDEN,
+            Self::ActivationLimitReached => StatusCode::FORBIDDEN,
+            Self::ActivationCooldown(..) => StatusCode::TOO_MANY_REQUESTS,
+            Self::DailyLimitReached => StatusCode::FORBIDDEN,
+            Self::IdempotencyConflict => StatusCode::CONFLICT,
+            Self::AlreadyRedeemed => StatusCode::CONFLICT,
+            Self::InvalidStatusTransition(..) => StatusCode::CONFLICT,
             Self::Json(..) => StatusCode::BAD_REQUEST,
             Self::NotFound => StatusCode::NOT_FOUND,
             Self::InvalidInput(..) => StatusCode::BAD_REQUEST,
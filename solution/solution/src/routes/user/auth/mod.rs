@@ -2,6 +2,8 @@ use actix_web::web::{scope, ServiceConfig};
 
 use crate::util::cors::default_cors;
 
+mod refresh;
+mod sessions;
 mod sign_in;
 mod sign_up;
 
@@ -10,6 +12,8 @@ pub fn config(cfg: &mut ServiceConfig) {
         scope("auth")
             .wrap(default_cors())
             .service(sign_up::post_handler)
-            .service(sign_in::post_handler),
+            .service(sign_in::post_handler)
+            .service(refresh::post_handler)
+            .configure(sessions::config),
     );
 }
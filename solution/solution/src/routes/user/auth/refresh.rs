@@ -0,0 +1,44 @@
+use actix_web::{
+    post,
+    web::{Data, Json},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    auth::AuthenticationError,
+    database::redis::RedisPool,
+    models::{RefreshToken, Session, TokenType},
+    routes::ApiError,
+};
+
+#[derive(Deserialize, Debug)]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+#[post("refresh")]
+pub async fn post_handler(
+    cache: Data<RedisPool>,
+    body: Json<RefreshRequest>,
+) -> Result<Json<RefreshResponse>, ApiError> {
+    let refresh_token: RefreshToken = body
+        .refresh_token
+        .parse()
+        .map_err(|_| AuthenticationError::InvalidCredentials)?;
+
+    let mut cache = cache.connect().await?;
+
+    let (token, refresh_token) =
+        Session::refresh(refresh_token, &[TokenType::User], &mut cache).await?;
+
+    Ok(Json(RefreshResponse {
+        token: token.to_string(),
+        refresh_token: refresh_token.to_string(),
+    }))
+}
+
+#[derive(Serialize, Debug)]
+struct RefreshResponse {
+    token: String,
+    refresh_token: String,
+}
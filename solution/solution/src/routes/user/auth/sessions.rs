@@ -0,0 +1,59 @@
+use actix_web::{
+    delete, get,
+    middleware::from_fn,
+    web::{scope, Data, Json, Path, ReqData, ServiceConfig},
+};
+
+use crate::{
+    auth::auth_middleware_usr,
+    database::redis::RedisPool,
+    models::{EmptyResponse, Session, SessionPath, Token},
+    routes::ApiError,
+};
+
+pub fn config(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("sessions")
+            .wrap(from_fn(auth_middleware_usr))
+            .service(get_handler)
+            .service(delete_all_handler)
+            .service(delete_handler),
+    );
+}
+
+#[get("")]
+async fn get_handler(
+    cache: Data<RedisPool>,
+    token: ReqData<Token>,
+) -> Result<Json<Vec<Session>>, ApiError> {
+    let mut cache = cache.connect().await?;
+
+    let sessions = Session::get_all(&token, &mut cache).await?;
+
+    Ok(Json(sessions))
+}
+
+#[delete("")]
+async fn delete_all_handler(
+    cache: Data<RedisPool>,
+    token: ReqData<Token>,
+) -> Result<EmptyResponse, ApiError> {
+    let mut cache = cache.connect().await?;
+
+    Session::revoke_all(&token, &mut cache).await?;
+
+    Ok(EmptyResponse::default())
+}
+
+#[delete("/{session_id}")]
+async fn delete_handler(
+    cache: Data<RedisPool>,
+    token: ReqData<Token>,
+    path: Path<SessionPath>,
+) -> Result<EmptyResponse, ApiError> {
+    let mut cache = cache.connect().await?;
+
+    Session::revoke(&token, path.session_id, &mut cache).await?;
+
+    Ok(EmptyResponse::default())
+}
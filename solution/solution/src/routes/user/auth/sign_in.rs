@@ -10,7 +10,7 @@ use validator::Validate;
 use crate::{
     auth::AuthenticationError,
     database::{models::DBUser, redis::RedisPool},
-    models::{Token, TokenType},
+    models::{ClientInfo, Session, TokenType},
     routes::ApiError,
     util::validate::validation_errors_to_string,
 };
@@ -28,6 +28,7 @@ struct SignInRequest {
 pub async fn post_handler(
     pool: Data<PgPool>,
     cache: Data<RedisPool>,
+    client: ClientInfo,
     body: Json<SignInRequest>,
 ) -> Result<Json<SignInResponse>, ApiError> {
     body.validate()
@@ -49,17 +50,17 @@ pub async fn post_handler(
 
     let mut cache = cache.connect().await?;
 
-    let token = Token::new(TokenType::User, user.id)
-        .into_db()
-        .insert(&mut cache)
-        .await?
-        .into_model()
-        .to_string();
+    let (token, refresh_token) =
+        Session::start(TokenType::User, user.id, client, &mut cache).await?;
 
-    Ok(Json(SignInResponse { token }))
+    Ok(Json(SignInResponse {
+        token: token.to_string(),
+        refresh_token: refresh_token.to_string(),
+    }))
 }
 
 #[derive(Serialize)]
 struct SignInResponse {
     token: String,
+    refresh_token: String,
 }
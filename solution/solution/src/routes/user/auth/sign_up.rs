@@ -12,7 +12,7 @@ use validator::Validate;
 use crate::{
     auth::AuthenticationError,
     database::{models::DBUser, redis::RedisPool},
-    models::{Token, TokenType, User, UserTargetSettings},
+    models::{ClientInfo, Session, TokenType, User, UserTargetSettings},
     routes::ApiError,
     util::validate::{validate_password, validation_errors_to_string},
 };
@@ -42,6 +42,7 @@ pub struct SignUpRequest {
 async fn post_handler(
     pool: Data<PgPool>,
     cache: Data<RedisPool>,
+    client: ClientInfo,
     body: Json<SignUpRequest>,
 ) -> Result<Json<SignUpResponse>, ApiError> {
     body.validate()
@@ -61,12 +62,7 @@ async fn post_handler(
     let mut transaction = pool.begin().await?;
     let mut cache = cache.connect().await?;
 
-    let token = Token::new(TokenType::User, id)
-        .into_db()
-        .insert(&mut cache)
-        .await?
-        .into_model()
-        .to_string();
+    let (token, refresh_token) = Session::start(TokenType::User, id, client, &mut cache).await?;
 
     User {
         id,
@@ -76,6 +72,7 @@ async fn post_handler(
         avatar_url: body.avatar_url.clone(),
         other: body.other.clone(),
         password_hash,
+        interests: vec![],
     }
     .into_db()
     .insert(&mut transaction)
@@ -83,10 +80,14 @@ async fn post_handler(
 
     transaction.commit().await?;
 
-    Ok(Json(SignUpResponse { token }))
+    Ok(Json(SignUpResponse {
+        token: token.to_string(),
+        refresh_token: refresh_token.to_string(),
+    }))
 }
 
 #[derive(Serialize, Debug)]
 struct SignUpResponse {
     token: String,
+    refresh_token: String,
 }
@@ -2,17 +2,26 @@ use actix_web::{
     get,
     middleware::from_fn,
     web::{scope, Data, Query, ReqData, ServiceConfig},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
+use log::warn;
 use serde::Deserialize;
 use sqlx::PgPool;
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     auth::auth_middleware_usr,
-    models::{Token, UserPromo},
+    database::{
+        models::{
+            DBPromoMode, DBPromoQuery, DBPromoSortField, DBPromoStatus, DBPromoViewKind,
+            DBSortOrder,
+        },
+        redis::RedisPool,
+    },
+    models::{PageRequest, SortFeedBy, Token, UserPromo},
     routes::ApiError,
-    util::validate::validation_errors_to_string,
+    util::{validate::validation_errors_to_string, views},
 };
 
 pub fn config(cfg: &mut ServiceConfig) {
@@ -35,11 +44,30 @@ struct PromosFeedQuery {
     category: Option<String>,
 
     active: Option<bool>,
+
+    company_id: Option<Uuid>,
+
+    mode: Option<DBPromoMode>,
+
+    #[serde(default)]
+    liked: bool,
+
+    #[serde(default)]
+    sort: SortFeedBy,
+
+    #[serde(default)]
+    order: DBSortOrder,
+
+    cursor: Option<String>,
+
+    total: Option<bool>,
 }
 
 #[get("")]
 async fn get_handler(
+    req: HttpRequest,
     pool: Data<PgPool>,
+    cache: Data<RedisPool>,
     token: ReqData<Token>,
     query: Query<PromosFeedQuery>,
 ) -> Result<HttpResponse, ApiError> {
@@ -49,17 +77,33 @@ async fn get_handler(
 
     let user = token.get_user(&**pool).await?;
 
-    let (promos, count) = UserPromo::get_pageable(
-        &user,
+    let page = PageRequest::new(
         query.limit,
         query.offset,
-        query.category.as_deref(),
-        query.active,
-        &**pool,
-    )
-    .await?;
-
-    Ok(HttpResponse::Ok()
-        .insert_header(("X-Total-Count", count))
-        .json(promos))
+        query.cursor.as_deref(),
+        query.total,
+    )?;
+
+    let mut filter = DBPromoQuery::new()
+        .targeting(user.id)
+        .category(query.category.clone())
+        .active(query.active)
+        .mode(query.mode)
+        .status(Some(DBPromoStatus::PUBLISHED))
+        .sort(DBPromoSortField::Id, query.order);
+    if let Some(company_id) = query.company_id {
+        filter = filter.company(company_id);
+    }
+    if query.liked {
+        filter = filter.liked_by(user.id);
+    }
+
+    let promos = UserPromo::get_pageable(&user, &filter, &page, query.sort, &**pool).await?;
+
+    let promo_ids: Vec<Uuid> = promos.items.iter().map(|promo| promo.promo_id).collect();
+    if let Err(e) = views::record(user.id, &promo_ids, DBPromoViewKind::IMPRESSION, &cache).await {
+        warn!("Recording promo impressions failed: {:?}", e);
+    }
+
+    Ok(promos.into_response(&req))
 }
@@ -12,7 +12,7 @@ use crate::{
     database::models::DBUser,
     models::{Token, UserTargetSettings},
     routes::ApiError,
-    util::validate::{validate_password, validation_errors_to_string},
+    util::validate::{validate_categories, validate_password, validation_errors_to_string},
 };
 
 #[derive(Deserialize, Validate, Debug)]
@@ -28,6 +28,9 @@ struct EditProfileRequest {
 
     #[validate(custom(function = "validate_password"), length(min = 8, max = 256))]
     password: Option<String>,
+
+    #[validate(custom(function = "validate_categories"), length(max = 20))]
+    interests: Option<Vec<String>>,
 }
 
 #[patch("")]
@@ -51,6 +54,16 @@ pub async fn patch_handler(
         );
     }
 
+    let interests: Option<Vec<String>> = body.interests.as_ref().map(|interests| {
+        let mut interests: Vec<String> = interests
+            .iter()
+            .map(|interest| interest.to_lowercase())
+            .collect();
+        interests.sort();
+        interests.dedup();
+        interests
+    });
+
     let mut transaction = pool.begin().await?;
 
     let user = DBUser::patch(
@@ -59,6 +72,7 @@ pub async fn patch_handler(
         body.surname.clone().as_deref(),
         body.avatar_url.clone().as_deref(),
         password_hash.clone().as_deref(),
+        interests.as_deref(),
         &mut transaction,
     )
     .await?
@@ -72,6 +86,7 @@ pub async fn patch_handler(
         email: user.email,
         avatar_url: user.avatar_url,
         other: user.other,
+        interests: user.interests,
     }))
 }
 
@@ -83,4 +98,5 @@ struct EditProfileResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     avatar_url: Option<String>,
     other: UserTargetSettings,
+    interests: Vec<String>,
 }
@@ -23,6 +23,7 @@ pub async fn get_handler(
         email: user.email,
         avatar_url: user.avatar_url,
         other: user.other,
+        interests: user.interests,
     }))
 }
 
@@ -34,4 +35,5 @@ pub struct GetProfileResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     avatar_url: Option<String>,
     other: UserTargetSettings,
+    interests: Vec<String>,
 }
@@ -1,46 +1,101 @@
 use actix_web::{
     post,
     web::{Data, Json, Path, ReqData},
+    HttpRequest,
 };
-use serde::Serialize;
+use log::warn;
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 
 use crate::{
     database::{models::DBPromo, redis::RedisPool},
-    models::{PromoPath, Token},
+    models::{PromoPath, Token, User},
     routes::ApiError,
+    util::idempotency::{self, IdempotencyClaim, IDEMPOTENCY_HEADER},
 };
 
 #[post("activate")]
 pub async fn post_handler(
+    req: HttpRequest,
     pool: Data<PgPool>,
     cache: Data<RedisPool>,
     token: ReqData<Token>,
     path: Path<PromoPath>,
 ) -> Result<Json<ActivatePromoResponse>, ApiError> {
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+    let idempotency_key = match req.headers().get(IDEMPOTENCY_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .filter(|key| idempotency::validate_key(key))
+                .ok_or_else(|| {
+                    ApiError::InvalidInput(format!("Invalid `{}` header", IDEMPOTENCY_HEADER))
+                })?,
+        ),
+        None => None,
+    };
+
+    let user = token.get_user(&**pool).await?;
+
+    let Some(key) = idempotency_key else {
+        return activate(&user, &path, &pool, &cache).await.map(Json);
+    };
+
+    let scope = format!("activate:{}:{}", user.id, path.promo_id);
+    match idempotency::claim(&scope, key, &cache).await? {
+        IdempotencyClaim::Acquired => {}
+        IdempotencyClaim::Completed(response) => return Ok(Json(serde_json::from_str(&response)?)),
+        IdempotencyClaim::InProgress => return Err(ApiError::IdempotencyConflict),
+    }
+
+    let result = activate(&user, &path, &pool, &cache).await;
+    let stored = match &result {
+        Ok(response) => {
+            idempotency::complete(&scope, key, &serde_json::to_string(response)?, &cache).await
+        }
+        Err(_) => idempotency::release(&scope, key, &cache).await,
+    };
+    if let Err(e) = stored {
+        warn!("Storing idempotent activation failed: {:?}", e);
+    }
+
+    result.map(Json)
+}
+
+async fn activate(
+    user: &User,
+    path: &PromoPath,
+    pool: &PgPool,
+    cache: &RedisPool,
+) -> Result<ActivatePromoResponse, ApiError> {
+    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, pool)
+        .await?
+        .filter(DBPromo::is_public)
+    {
         promo
     } else {
         return Err(ApiError::NotFound);
     }
     .into_model();
 
+    if let Some(promo) = promo.get_issued_code(user.id, pool).await? {
+        return Ok(ActivatePromoResponse { promo });
+    }
+
     if !promo.active {
         return Err(ApiError::PromoExpired);
     }
 
-    let user = token.get_user(&**pool).await?;
-
-    if !user.matches_target(promo.target.clone()) {
+    if !user.matches_target(promo.id, pool).await? {
         return Err(ApiError::NotPromoTarget);
     }
 
-    let promo = promo.get_code(&user, &**pool, &cache).await?;
+    let promo = promo.get_code(user, pool, cache).await?;
 
-    Ok(Json(ActivatePromoResponse { promo }))
+    Ok(ActivatePromoResponse { promo })
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 struct ActivatePromoResponse {
     promo: String,
 }
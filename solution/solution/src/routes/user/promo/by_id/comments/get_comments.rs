@@ -1,7 +1,7 @@
 use actix_web::{
     get,
     web::{Data, Path, Query},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 use serde::Deserialize;
 use sqlx::PgPool;
@@ -9,7 +9,7 @@ use validator::Validate;
 
 use crate::{
     database::models::DBComment,
-    models::{Comment, PromoPath},
+    models::{Comment, Cursor, Page, PageRequest, PromoPath},
     routes::ApiError,
 };
 
@@ -20,25 +20,32 @@ struct GetCommentsQuery {
 
     #[validate(range(min = 0))]
     offset: Option<u32>,
+
+    cursor: Option<String>,
+
+    total: Option<bool>,
 }
 
 #[get("")]
 pub async fn get_handler(
+    req: HttpRequest,
     pool: Data<PgPool>,
     path: Path<PromoPath>,
     query: Query<GetCommentsQuery>,
 ) -> Result<HttpResponse, ApiError> {
-    let (comments, count) = DBComment::get_pageable(
-        path.promo_id,
-        query.limit.unwrap_or(10).into(),
-        query.offset.unwrap_or(0).into(),
-        &**pool,
-    )
-    .await?;
-
-    let comments: Vec<Comment> = comments.into_iter().map(DBComment::into_model).collect();
-
-    Ok(HttpResponse::Ok()
-        .insert_header(("X-Total-Count", count))
-        .json(comments))
+    let page = PageRequest::new(
+        query.limit,
+        query.offset,
+        query.cursor.as_deref(),
+        query.total,
+    )?;
+
+    let (comments, count) = DBComment::get_pageable(path.promo_id, &page, &**pool).await?;
+
+    let comments: Page<Comment> = Page::new(comments, count, &page, |comment: &DBComment| {
+        Cursor::with_date(comment.date, comment.id)
+    })
+    .map(DBComment::into_model);
+
+    Ok(comments.into_response(&req))
 }
@@ -32,7 +32,10 @@ pub async fn post_handler(
     body.validate()
         .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
 
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool)
+        .await?
+        .filter(DBPromo::is_public)
+    {
         promo
     } else {
         return Err(ApiError::NotFound);
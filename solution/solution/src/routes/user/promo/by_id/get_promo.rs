@@ -2,26 +2,39 @@ use actix_web::{
     get,
     web::{Data, Json, Path, ReqData},
 };
+use log::warn;
 use sqlx::PgPool;
 
 use crate::{
-    database::models::DBPromo,
+    database::{
+        models::{DBPromo, DBPromoViewKind},
+        redis::RedisPool,
+    },
     models::{PromoPath, Token, UserPromo},
     routes::ApiError,
+    util::views,
 };
 
 #[get("")]
 pub async fn get_handler(
     pool: Data<PgPool>,
+    cache: Data<RedisPool>,
     token: ReqData<Token>,
     path: Path<PromoPath>,
 ) -> Result<Json<UserPromo>, ApiError> {
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool)
+        .await?
+        .filter(DBPromo::is_public)
+    {
         promo
     } else {
         return Err(ApiError::NotFound);
     };
 
+    if let Err(e) = views::record(token.entity, &[promo.id], DBPromoViewKind::VIEW, &cache).await {
+        warn!("Recording promo view failed: {:?}", e);
+    }
+
     Ok(Json(
         promo.into_model().into_user(token.entity, &**pool).await?,
     ))
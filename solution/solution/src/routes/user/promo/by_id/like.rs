@@ -16,7 +16,10 @@ pub async fn post_handler(
     token: ReqData<Token>,
     path: Path<PromoPath>,
 ) -> Result<EmptyResponse, ApiError> {
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool)
+        .await?
+        .filter(DBPromo::is_public)
+    {
         promo
     } else {
         return Err(ApiError::NotFound);
@@ -36,7 +39,10 @@ pub async fn delete_handler(
     token: ReqData<Token>,
     path: Path<PromoPath>,
 ) -> Result<EmptyResponse, ApiError> {
-    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool).await? {
+    let promo = if let Some(promo) = DBPromo::get_by_id(path.promo_id, &**pool)
+        .await?
+        .filter(DBPromo::is_public)
+    {
         promo
     } else {
         return Err(ApiError::NotFound);
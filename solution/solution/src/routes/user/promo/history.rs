@@ -1,13 +1,17 @@
 use actix_web::{
     get,
     web::{Data, Query, ReqData},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 use serde::Deserialize;
 use sqlx::PgPool;
 use validator::Validate;
 
-use crate::{models::Token, routes::ApiError, util::validate::validation_errors_to_string};
+use crate::{
+    models::{PageRequest, Token},
+    routes::ApiError,
+    util::validate::validation_errors_to_string,
+};
 
 #[derive(Deserialize, Validate)]
 struct PromosHistoryQuery {
@@ -16,10 +20,15 @@ struct PromosHistoryQuery {
 
     #[validate(range(min = 0))]
     offset: Option<u32>,
+
+    cursor: Option<String>,
+
+    total: Option<bool>,
 }
 
 #[get("/history")]
 pub async fn get_handler(
+    req: HttpRequest,
     pool: Data<PgPool>,
     token: ReqData<Token>,
     query: Query<PromosHistoryQuery>,
@@ -30,15 +39,14 @@ pub async fn get_handler(
 
     let user = token.get_user(&**pool).await?;
 
-    let (promos, count) = user
-        .get_activation_history(
-            query.limit,
-            query.offset,
-            &**pool,
-        )
-        .await?;
-
-    Ok(HttpResponse::Ok()
-        .insert_header(("X-Total-Count", count))
-        .json(promos))
+    let page = PageRequest::new(
+        query.limit,
+        query.offset,
+        query.cursor.as_deref(),
+        query.total,
+    )?;
+
+    let promos = user.get_activation_history(&page, &**pool).await?;
+
+    Ok(promos.into_response(&req))
 }
@@ -7,6 +7,7 @@ use crate::{auth::auth_middleware_usr, util::cors::default_cors};
 
 mod by_id;
 mod history;
+mod search;
 
 pub fn config(cfg: &mut ServiceConfig) {
     cfg.service(
@@ -14,6 +15,7 @@ pub fn config(cfg: &mut ServiceConfig) {
             .wrap(default_cors())
             .wrap(from_fn(auth_middleware_usr))
             .service(history::get_handler)
+            .service(search::get_handler)
             .configure(by_id::config),
     );
 }
@@ -0,0 +1,70 @@
+use actix_web::{
+    get,
+    web::{Data, Query, ReqData},
+    HttpRequest, HttpResponse,
+};
+use log::warn;
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    database::{models::DBPromoViewKind, redis::RedisPool},
+    models::{PageRequest, Token, UserPromo},
+    routes::ApiError,
+    util::{validate::validation_errors_to_string, views},
+};
+
+#[derive(Deserialize, Validate)]
+struct PromosSearchQuery {
+    #[validate(length(min = 1, max = 200))]
+    q: String,
+
+    #[validate(range(min = 0))]
+    limit: Option<u32>,
+
+    #[validate(range(min = 0))]
+    offset: Option<u32>,
+
+    #[validate(length(min = 2, max = 20))]
+    category: Option<String>,
+
+    active: Option<bool>,
+
+    total: Option<bool>,
+}
+
+#[get("/search")]
+pub async fn get_handler(
+    req: HttpRequest,
+    pool: Data<PgPool>,
+    cache: Data<RedisPool>,
+    token: ReqData<Token>,
+    query: Query<PromosSearchQuery>,
+) -> Result<HttpResponse, ApiError> {
+    query
+        .validate()
+        .map_err(|err| ApiError::InvalidInput(validation_errors_to_string(err, None)))?;
+
+    let page = PageRequest::new(query.limit, query.offset, None, query.total)?;
+
+    let user = token.get_user(&**pool).await?;
+
+    let promos = UserPromo::search(
+        &user,
+        query.q.trim(),
+        &page,
+        query.category.as_deref(),
+        query.active,
+        &**pool,
+    )
+    .await?;
+
+    let promo_ids: Vec<Uuid> = promos.items.iter().map(|promo| promo.promo_id).collect();
+    if let Err(e) = views::record(user.id, &promo_ids, DBPromoViewKind::IMPRESSION, &cache).await {
+        warn!("Recording promo impressions failed: {:?}", e);
+    }
+
+    Ok(promos.into_response(&req))
+}
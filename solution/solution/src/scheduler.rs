@@ -1,10 +1,17 @@
+use std::time::Duration;
+
 use actix_rt::Arbiter;
+use chrono::{DateTime, Utc};
 use futures::StreamExt;
-use sqlx::{query, PgPool};
+use log::warn;
+use sqlx::{postgres::PgListener, query, PgPool};
 use tokio_stream::wrappers::IntervalStream;
 
 use crate::database::models::DatabaseError;
 
+const PROMO_SCHEDULE_CHANNEL: &str = "promo_schedule";
+const PROMO_SCHEDULE_MAX_SLEEP: Duration = Duration::from_secs(60 * 60);
+
 pub struct Scheduler {
     arbiter: Arbiter,
 }
@@ -32,6 +39,13 @@ impl Scheduler {
 
         self.arbiter.spawn(future);
     }
+
+    pub fn spawn<R>(&mut self, task: R)
+    where
+        R: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.arbiter.spawn(task);
+    }
 }
 
 impl Drop for Scheduler {
@@ -40,9 +54,74 @@ impl Drop for Scheduler {
     }
 }
 
-pub async fn update_promos_active(pool: &PgPool) -> Result<(), DatabaseError> {
-    query!("SELECT update_promos_active()")
-        .execute(&*pool)
-        .await?;
-    Ok(())
+/// Updates `active` on promos whose activity period started or ended after
+/// `since`, or on every promo when it's `None`. Returns the time to pass as
+/// `since` on the next run.
+pub async fn update_due_promos_active(
+    since: Option<DateTime<Utc>>,
+    pool: &PgPool,
+) -> Result<DateTime<Utc>, DatabaseError> {
+    let row = query!(
+        r#"SELECT update_due_promos_active($1) AS "checked_at!""#,
+        since
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(row.checked_at)
+}
+
+/// Flips `active` on promos exactly when their activity period starts or ends.
+/// Sleeps until the nearest boundary and wakes up early whenever a promo's
+/// dates change.
+pub async fn watch_promo_transitions(pool: PgPool) {
+    let mut listener = match PgListener::connect_with(&pool).await {
+        Ok(mut listener) => match listener.listen(PROMO_SCHEDULE_CHANNEL).await {
+            Ok(()) => Some(listener),
+            Err(e) => {
+                warn!("Listening for promo schedule changes failed: {:?}", e);
+                None
+            }
+        },
+        Err(e) => {
+            warn!("Connecting promo schedule listener failed: {:?}", e);
+            None
+        }
+    };
+
+    let mut since = None;
+    loop {
+        match update_due_promos_active(since, &pool).await {
+            Ok(checked_at) => since = Some(checked_at),
+            Err(e) => warn!("Updating `active` field failed: {:?}", e),
+        }
+
+        let sleep = match query!("SELECT next_promo_transition() AS next")
+            .fetch_one(&pool)
+            .await
+        {
+            Ok(row) => match row.next {
+                Some(next) => (next - Utc::now())
+                    .to_std()
+                    .unwrap_or(Duration::ZERO)
+                    .min(PROMO_SCHEDULE_MAX_SLEEP),
+                None => PROMO_SCHEDULE_MAX_SLEEP,
+            },
+            Err(e) => {
+                warn!("Fetching next promo transition failed: {:?}", e);
+                PROMO_SCHEDULE_MAX_SLEEP
+            }
+        };
+
+        if let Some(active_listener) = &mut listener {
+            if let Ok(Err(e)) = actix_rt::time::timeout(sleep, active_listener.recv()).await {
+                warn!(
+                    "Promo schedule listener failed, falling back to polling: {:?}",
+                    e
+                );
+                listener = None;
+            }
+        } else {
+            actix_rt::time::sleep(sleep).await;
+        }
+    }
 }
@@ -0,0 +1,100 @@
+use rand_chacha::{
+    rand_core::{RngCore, SeedableRng},
+    ChaCha20Rng,
+};
+
+pub const MAX_CODE_LENGTH: usize = 30;
+pub const DEFAULT_CODE_ALPHABET: &str = "ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+pub const DEFAULT_CODE_LENGTH: usize = 10;
+
+pub fn validate_code(code: &str) -> bool {
+    (1..=MAX_CODE_LENGTH).contains(&code.chars().count())
+        && code.chars().all(|c| c.is_ascii_graphic())
+}
+
+/// Extracts the code from a line of an uploaded file: the first column of a
+/// CSV row or the whole line of a plain list. Returns `None` for blank lines.
+pub fn parse_code_line(line: &str) -> Option<&str> {
+    let code = line.split(',').next().unwrap_or_default().trim();
+    let code = code
+        .strip_prefix('"')
+        .and_then(|code| code.strip_suffix('"'))
+        .unwrap_or(code)
+        .trim();
+
+    (!code.is_empty()).then_some(code)
+}
+
+/// Computes the Luhn mod N check character of `code` over `alphabet`.
+/// Returns `None` if `code` has characters outside of the alphabet.
+pub fn luhn_check_char(code: &str, alphabet: &[char]) -> Option<char> {
+    let base = alphabet.len();
+    let mut factor = 2;
+    let mut sum = 0;
+
+    for c in code.chars().rev() {
+        let addend = factor * alphabet.iter().position(|&a| a == c)?;
+        sum += addend / base + addend % base;
+        factor = if factor == 2 { 1 } else { 2 };
+    }
+
+    Some(alphabet[(base - sum % base) % base])
+}
+
+pub struct CodeGenerator {
+    alphabet: Vec<char>,
+    length: usize,
+    prefix: String,
+    check_digit: bool,
+    rng: ChaCha20Rng,
+}
+
+impl CodeGenerator {
+    pub fn new(alphabet: &str, length: usize, prefix: &str, check_digit: bool) -> Self {
+        Self {
+            alphabet: alphabet.chars().collect(),
+            length,
+            prefix: prefix.to_string(),
+            check_digit,
+            rng: ChaCha20Rng::from_entropy(),
+        }
+    }
+
+    /// Length of every generated code, prefix and check character included.
+    pub fn code_length(&self) -> usize {
+        self.prefix.chars().count() + self.length + usize::from(self.check_digit)
+    }
+
+    /// Number of distinct codes the generator can produce.
+    pub fn capacity(&self) -> f64 {
+        (self.alphabet.len() as f64).powi(self.length as i32)
+    }
+
+    pub fn generate(&mut self) -> String {
+        let body = (0..self.length)
+            .map(|_| {
+                let index = self.random_index();
+                self.alphabet[index]
+            })
+            .collect::<String>();
+
+        let mut code = self.prefix.clone();
+        code.push_str(&body);
+        if self.check_digit {
+            code.extend(luhn_check_char(&body, &self.alphabet));
+        }
+        code
+    }
+
+    fn random_index(&mut self) -> usize {
+        let base = self.alphabet.len() as u32;
+        // Rejection sampling keeps every character equally likely.
+        let zone = u32::MAX - u32::MAX % base;
+        loop {
+            let value = self.rng.next_u32();
+            if value < zone {
+                return (value % base) as usize;
+            }
+        }
+    }
+}
@@ -13,29 +13,36 @@ pub fn decode_uuid(engine: &GeneralPurpose, value: &str) -> Result<Uuid, ()> {
     Uuid::from_slice(uuid.as_slice()).map_err(|_| ())
 }
 
+/// Accepts either a bare `YYYY-MM-DD` date (midnight UTC) or a full RFC 3339
+/// timestamp. Midnight UTC is written back as a bare date.
 pub mod promo_date_format {
-    use chrono::{DateTime, NaiveDateTime, Utc};
+    use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
     use serde::{Deserialize, Serializer};
 
-    const DATE_FORMAT: &'static str = "%F %H:%M:%S";
+    const DATE_FORMAT: &'static str = "%F";
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = format!("{} 00:00:00", String::deserialize(deserializer)?);
-        let dt =
-            NaiveDateTime::parse_from_str(&s, DATE_FORMAT).map_err(serde::de::Error::custom)?;
-        Ok(Some(DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc)))
+        let s = String::deserialize(deserializer)?;
+        if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
+            return Ok(Some(dt.with_timezone(&Utc)));
+        }
+
+        let date = NaiveDate::parse_from_str(&s, DATE_FORMAT).map_err(serde::de::Error::custom)?;
+        Ok(Some(date.and_time(NaiveTime::MIN).and_utc()))
     }
 
     pub fn serialize<S>(date: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let s = format!("{}", date.format(DATE_FORMAT));
-        let s = s.split_whitespace().collect::<Vec<&str>>()[0];
-        serializer.serialize_str(&s)
+        if date.time() == NaiveTime::MIN {
+            serializer.serialize_str(&date.format(DATE_FORMAT).to_string())
+        } else {
+            serializer.serialize_str(&date.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+        }
     }
 }
 pub fn serialize_opt_promo_date<S>(
@@ -0,0 +1,78 @@
+use crate::{
+    database::{models::DatabaseError, redis::RedisPool},
+    IDEMPOTENCY_KEY_TTL,
+};
+
+const IDEMPOTENCY_NAMESPACE: &str = "idempotency";
+const IDEMPOTENCY_PENDING: &str = "";
+const IDEMPOTENCY_PENDING_EXPIRY: i64 = 60;
+
+pub const IDEMPOTENCY_HEADER: &str = "Idempotency-Key";
+
+pub enum IdempotencyClaim {
+    Acquired,
+    Completed(String),
+    InProgress,
+}
+
+pub fn validate_key(key: &str) -> bool {
+    (1..=255).contains(&key.len()) && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Reserves `key` within `scope` for the current request. Returns the stored
+/// response if an earlier request with the same key has already finished.
+pub async fn claim(
+    scope: &str,
+    key: &str,
+    cache: &RedisPool,
+) -> Result<IdempotencyClaim, DatabaseError> {
+    let mut cache = cache.connect().await?;
+    let id = format!("{scope}:{key}");
+
+    if cache
+        .set_if_absent(
+            IDEMPOTENCY_NAMESPACE,
+            &id,
+            IDEMPOTENCY_PENDING,
+            Some(IDEMPOTENCY_PENDING_EXPIRY),
+        )
+        .await?
+    {
+        return Ok(IdempotencyClaim::Acquired);
+    }
+
+    Ok(match cache.get(IDEMPOTENCY_NAMESPACE, &id).await? {
+        Some(response) if response != IDEMPOTENCY_PENDING => IdempotencyClaim::Completed(response),
+        _ => IdempotencyClaim::InProgress,
+    })
+}
+
+pub async fn complete(
+    scope: &str,
+    key: &str,
+    response: &str,
+    cache: &RedisPool,
+) -> Result<(), DatabaseError> {
+    let expiry = IDEMPOTENCY_KEY_TTL()
+        .parse()
+        .expect("`IDEMPOTENCY_KEY_TTL` must be a number of seconds");
+
+    cache
+        .connect()
+        .await?
+        .set(
+            IDEMPOTENCY_NAMESPACE,
+            &format!("{scope}:{key}"),
+            response,
+            Some(expiry),
+        )
+        .await
+}
+
+pub async fn release(scope: &str, key: &str, cache: &RedisPool) -> Result<(), DatabaseError> {
+    cache
+        .connect()
+        .await?
+        .delete(IDEMPOTENCY_NAMESPACE, format!("{scope}:{key}"))
+        .await
+}
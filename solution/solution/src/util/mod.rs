@@ -1,6 +1,11 @@
 pub mod antifraud;
+pub mod codes;
 pub mod convertions;
 pub mod cors;
 pub mod env;
+pub mod idempotency;
+pub mod ranking;
 pub mod validate;
 pub mod values;
+pub mod views;
+pub mod webhooks;
@@ -0,0 +1,254 @@
+use chrono::{DateTime, Utc};
+
+use crate::{database::models::DBPromoMode, models::Promo};
+
+const CATEGORY_WEIGHT: f64 = 3.0;
+const POPULARITY_WEIGHT: f64 = 2.0;
+const FRESHNESS_WEIGHT: f64 = 1.5;
+const CAPACITY_WEIGHT: f64 = 1.0;
+
+const POPULARITY_SATURATION: f64 = 1000.0;
+const FRESHNESS_HALF_LIFE_DAYS: f64 = 14.0;
+
+pub fn category_score(promo: &Promo, interests: &[String]) -> f64 {
+    let categories = match &promo.target.categories {
+        Some(categories) if !categories.is_empty() => categories,
+        _ => return 0.0,
+    };
+
+    let matching = categories
+        .iter()
+        .filter(|category| interests.contains(&category.to_lowercase()))
+        .count();
+
+    matching as f64 / categories.len() as f64
+}
+
+pub fn popularity_score(promo: &Promo) -> f64 {
+    let interactions =
+        f64::from(promo.like_count.max(0)) + 2.0 * f64::from(promo.used_count.max(0));
+
+    ((1.0 + interactions).ln() / (1.0 + POPULARITY_SATURATION).ln()).min(1.0)
+}
+
+pub fn freshness_score(promo: &Promo, now: DateTime<Utc>) -> f64 {
+    let created_at = promo
+        .id
+        .get_timestamp()
+        .map(|timestamp| timestamp.to_unix().0 as i64)
+        .and_then(|seconds| DateTime::<Utc>::from_timestamp(seconds, 0));
+    let started_at = match (promo.active_from, created_at) {
+        (Some(active_from), Some(created_at)) => active_from.max(created_at),
+        (Some(date), None) | (None, Some(date)) => date,
+        (None, None) => return 0.0,
+    };
+
+    let age_days = ((now - started_at).num_seconds() as f64 / 86400.0).max(0.0);
+
+    0.5_f64.powf(age_days / FRESHNESS_HALF_LIFE_DAYS)
+}
+
+pub fn capacity_score(promo: &Promo) -> f64 {
+    let (capacity, remaining) = match promo.mode {
+        DBPromoMode::COMMON => (
+            i64::from(promo.max_count),
+            i64::from(promo.max_count - promo.used_count),
+        ),
+        DBPromoMode::UNIQUE => (
+            promo.promo_unique_count.unwrap_or(0),
+            promo.promo_unique_available.unwrap_or(0),
+        ),
+    };
+    if capacity <= 0 {
+        return 0.0;
+    }
+
+    (remaining as f64 / capacity as f64).clamp(0.0, 1.0)
+}
+
+pub fn relevance_score(promo: &Promo, interests: &[String], now: DateTime<Utc>) -> f64 {
+    CATEGORY_WEIGHT * category_score(promo, interests)
+        + POPULARITY_WEIGHT * popularity_score(promo)
+        + FRESHNESS_WEIGHT * freshness_score(promo, now)
+        + CAPACITY_WEIGHT * capacity_score(promo)
+}
+
+pub fn rank_by_relevance(promos: &mut [Promo], interests: &[String], now: DateTime<Utc>) {
+    promos.sort_by(|a, b| {
+        relevance_score(b, interests, now)
+            .total_cmp(&relevance_score(a, interests, now))
+            .then_with(|| b.id.cmp(&a.id))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use uuid::{NoContext, Timestamp, Uuid};
+
+    use super::*;
+    use crate::{database::models::DBPromoStatus, models::PromoTarget};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn created_at(date: DateTime<Utc>) -> Uuid {
+        Uuid::new_v7(Timestamp::from_unix(
+            NoContext,
+            date.timestamp() as u64,
+            date.timestamp_subsec_nanos(),
+        ))
+    }
+
+    fn promo() -> Promo {
+        Promo {
+            id: created_at(now()),
+            description: String::new(),
+            image_url: None,
+            target: PromoTarget {
+                age_from: None,
+                age_until: None,
+                country: None,
+                categories: None,
+            },
+            max_count: 100,
+            active_from: None,
+            active_until: None,
+            mode: DBPromoMode::COMMON,
+            promo_common: Some("COMMON".to_string()),
+            promo_unique_count: None,
+            promo_unique_available: None,
+            company_id: Uuid::nil(),
+            company_name: String::new(),
+            like_count: 0,
+            used_count: 0,
+            comment_count: 0,
+            active: true,
+            per_user_limit: None,
+            cooldown: None,
+            max_per_day: None,
+            status: DBPromoStatus::PUBLISHED,
+        }
+    }
+
+    fn categories(promo: &mut Promo, categories: &[&str]) {
+        promo.target.categories = Some(categories.iter().map(|c| c.to_string()).collect());
+    }
+
+    #[test]
+    fn category_score_is_share_of_matching_categories() {
+        let interests = vec!["food".to_string(), "travel".to_string()];
+        let mut promo = promo();
+
+        assert_eq!(category_score(&promo, &interests), 0.0);
+
+        categories(&mut promo, &[]);
+        assert_eq!(category_score(&promo, &interests), 0.0);
+
+        categories(&mut promo, &["Food", "sport"]);
+        assert_eq!(category_score(&promo, &interests), 0.5);
+        assert_eq!(category_score(&promo, &[]), 0.0);
+
+        categories(&mut promo, &["TRAVEL", "food"]);
+        assert_eq!(category_score(&promo, &interests), 1.0);
+    }
+
+    #[test]
+    fn popularity_score_grows_and_saturates() {
+        let mut promo = promo();
+        assert_eq!(popularity_score(&promo), 0.0);
+
+        promo.like_count = 10;
+        let liked = popularity_score(&promo);
+        assert!(liked > 0.0 && liked < 1.0);
+
+        promo.like_count = 0;
+        promo.used_count = 5;
+        assert_eq!(popularity_score(&promo), liked);
+
+        promo.used_count = 50;
+        assert!(popularity_score(&promo) > liked);
+
+        promo.used_count = 1_000_000;
+        assert_eq!(popularity_score(&promo), 1.0);
+
+        promo.used_count = -5;
+        promo.like_count = -5;
+        assert_eq!(popularity_score(&promo), 0.0);
+    }
+
+    #[test]
+    fn freshness_score_halves_every_half_life() {
+        let mut promo = promo();
+        assert_eq!(freshness_score(&promo, now()), 1.0);
+
+        let half_life = Duration::days(FRESHNESS_HALF_LIFE_DAYS as i64);
+        assert_eq!(freshness_score(&promo, now() + half_life), 0.5);
+        assert_eq!(freshness_score(&promo, now() + half_life * 2), 0.25);
+
+        // Not started yet
+        assert_eq!(freshness_score(&promo, now() - half_life), 1.0);
+
+        // Counted from whichever is later, creation or start of activity
+        promo.id = created_at(now() - half_life * 4);
+        assert_eq!(freshness_score(&promo, now()), 0.0625);
+        promo.active_from = Some(now() - half_life);
+        assert_eq!(freshness_score(&promo, now()), 0.5);
+    }
+
+    #[test]
+    fn capacity_score_is_share_of_remaining_codes() {
+        let mut promo = promo();
+        assert_eq!(capacity_score(&promo), 1.0);
+
+        promo.used_count = 25;
+        assert_eq!(capacity_score(&promo), 0.75);
+
+        promo.used_count = 150;
+        assert_eq!(capacity_score(&promo), 0.0);
+
+        promo.max_count = 0;
+        promo.used_count = 0;
+        assert_eq!(capacity_score(&promo), 0.0);
+
+        promo.mode = DBPromoMode::UNIQUE;
+        promo.max_count = 1;
+        assert_eq!(capacity_score(&promo), 0.0);
+
+        promo.promo_unique_count = Some(4);
+        promo.promo_unique_available = Some(3);
+        assert_eq!(capacity_score(&promo), 0.75);
+
+        // Codes added after some were issued count towards the capacity
+        promo.promo_unique_count = Some(8);
+        promo.promo_unique_available = Some(7);
+        assert_eq!(capacity_score(&promo), 0.875);
+    }
+
+    #[test]
+    fn ranks_by_score_then_newest_id() {
+        let interests = vec!["food".to_string()];
+
+        let older = promo();
+        let mut newer = promo();
+        newer.id = created_at(now() + Duration::milliseconds(1));
+        let mut popular = promo();
+        popular.id = created_at(now() - Duration::days(365));
+        popular.like_count = 1000;
+        let mut matching = promo();
+        matching.id = created_at(now() - Duration::days(365));
+        categories(&mut matching, &["food"]);
+        matching.like_count = 1000;
+
+        let ids = [matching.id, popular.id, newer.id, older.id];
+        let mut promos = vec![older, popular, newer, matching];
+        rank_by_relevance(&mut promos, &interests, now());
+
+        assert_eq!(promos.iter().map(|p| p.id).collect::<Vec<_>>(), ids);
+        assert_eq!(
+            relevance_score(&promos[2], &interests, now()),
+            relevance_score(&promos[3], &interests, now())
+        );
+    }
+}
@@ -41,14 +41,20 @@ pub fn validate_target(target: &PromoTarget) -> Result<(), ValidationError> {
     }
 
     if let Some(categories) = &target.categories {
-        for category in categories {
-            match category.len() {
-                2..=20 => (),
-                _ => {
-                    return Err(ValidationError::new(
-                        "`categories` item length must be between 2 and 20",
-                    ));
-                }
+        validate_categories(categories)?;
+    }
+
+    Ok(())
+}
+
+pub fn validate_categories(categories: &Vec<String>) -> Result<(), ValidationError> {
+    for category in categories {
+        match category.len() {
+            2..=20 => (),
+            _ => {
+                return Err(ValidationError::new(
+                    "`categories` item length must be between 2 and 20",
+                ));
             }
         }
     }
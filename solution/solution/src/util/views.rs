@@ -0,0 +1,79 @@
+use chrono::Utc;
+use log::warn;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database::{
+    models::{DBPromoView, DBPromoViewKind, DatabaseError},
+    redis::RedisPool,
+};
+
+const VIEWS_NAMESPACE: &str = "promo_views";
+const VIEWS_QUEUE_NAMESPACE: &str = "promo_views_queue";
+const VIEWS_QUEUE_ID: &str = "pending";
+const VIEWS_DEDUP_EXPIRY: i64 = 60 * 60 * 48;
+const VIEWS_FLUSH_BATCH_SIZE: usize = 1000;
+
+pub async fn record(
+    user_id: Uuid,
+    promo_ids: &[Uuid],
+    kind: DBPromoViewKind,
+    cache: &RedisPool,
+) -> Result<(), DatabaseError> {
+    let mut cache = cache.connect().await?;
+    let day = Utc::now().date_naive();
+
+    let mut views = vec![];
+    for &promo_id in promo_ids {
+        let id = format!("{kind}:{promo_id}:{user_id}:{day}");
+        if cache
+            .set_if_absent(VIEWS_NAMESPACE, &id, "true", Some(VIEWS_DEDUP_EXPIRY))
+            .await?
+        {
+            views.push(serde_json::to_string(&DBPromoView {
+                promo_id,
+                user_id,
+                kind,
+                day,
+            })?);
+        }
+    }
+
+    cache
+        .push_to_list(VIEWS_QUEUE_NAMESPACE, VIEWS_QUEUE_ID, &views)
+        .await
+}
+
+pub async fn flush(cache: &RedisPool, pool: &PgPool) -> Result<(), DatabaseError> {
+    let mut cache = cache.connect().await?;
+
+    loop {
+        let items = cache
+            .pop_from_list(
+                VIEWS_QUEUE_NAMESPACE,
+                VIEWS_QUEUE_ID,
+                VIEWS_FLUSH_BATCH_SIZE,
+            )
+            .await?;
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let views: Vec<DBPromoView> = items
+            .iter()
+            .filter_map(|item| serde_json::from_str(item).ok())
+            .collect();
+
+        if let Err(e) = DBPromoView::insert_many(&views, pool).await {
+            warn!("Flushing promo views failed, requeueing them: {:?}", e);
+            cache
+                .push_to_list(VIEWS_QUEUE_NAMESPACE, VIEWS_QUEUE_ID, &items)
+                .await?;
+            return Err(e);
+        }
+
+        if items.len() < VIEWS_FLUSH_BATCH_SIZE {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    error::Error,
+    fmt::Write,
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+    sync::Arc,
+    time::Duration as StdDuration,
+};
+
+use chrono::{Duration, Utc};
+use futures::{stream, StreamExt};
+use hmac::{Hmac, Mac};
+use log::warn;
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+    Client, Url,
+};
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::PgPool;
+
+use crate::{
+    database::{
+        events::EventStream,
+        models::{DBWebhook, DBWebhookDelivery, DBWebhookDeliveryStatus, DatabaseError},
+    },
+    WEBHOOK_MAX_ATTEMPTS,
+};
+
+const DELIVERY_BATCH_SIZE: i64 = 50;
+const DELIVERY_CONCURRENCY: usize = 10;
+const DELIVERY_TIMEOUT: StdDuration = StdDuration::from_secs(10);
+// Twice the time a batch takes when every endpoint hangs until the timeout,
+// so a lease never runs out while its delivery is still being sent.
+const DELIVERY_LEASE_SECONDS: u64 = 2
+    * DELIVERY_TIMEOUT.as_secs()
+    * (DELIVERY_BATCH_SIZE as u64).div_ceil(DELIVERY_CONCURRENCY as u64);
+const EVENTS_RETRY_DELAY: StdDuration = StdDuration::from_secs(5);
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 60 * 60;
+
+pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+fn retry_delay(attempts: i32) -> Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    Duration::seconds((RETRY_BASE_SECONDS << exponent).min(RETRY_MAX_SECONDS))
+}
+
+/// Whether an address is reachable from the internet. Webhooks must not be
+/// able to reach the service's own network or cloud metadata endpoints.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && ip.octets()[2] == 0)
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Checks what can be told about a webhook URL without resolving its host:
+/// it must use https and must not point at a private address or localhost.
+pub fn check_url(url: &str) -> Result<Url, String> {
+    let url = Url::parse(url).map_err(|err| format!("invalid webhook url: {err}"))?;
+
+    if url.scheme() != "https" {
+        return Err("webhook url must use https".to_string());
+    }
+
+    let blocked = match url.host_str().map(|host| host.trim_matches(['[', ']'])) {
+        Some(host) => match host.parse() {
+            Ok(ip) => !is_public_address(ip),
+            Err(_) => {
+                let host = host.trim_end_matches('.').to_ascii_lowercase();
+                host == "localhost" || host.ends_with(".localhost")
+            }
+        },
+        None => true,
+    };
+    if blocked {
+        return Err("webhook url must point to a public address".to_string());
+    }
+
+    Ok(url)
+}
+
+fn resolve_public(host: &str) -> Result<Vec<SocketAddr>, Box<dyn Error + Send + Sync>> {
+    let addrs: Vec<SocketAddr> = (host, 0)
+        .to_socket_addrs()?
+        .filter(|addr| is_public_address(addr.ip()))
+        .collect();
+
+    if addrs.is_empty() {
+        return Err(format!("`{host}` doesn't resolve to a public address").into());
+    }
+
+    Ok(addrs)
+}
+
+/// Resolves webhook hosts to public addresses only, so a host can't be
+/// pointed at a private network after the webhook was registered.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs = actix_rt::task::spawn_blocking(move || resolve_public(&host)).await??;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Checks a webhook URL when it is registered, including where its host
+/// currently resolves to.
+pub async fn check_destination(url: &str) -> Result<(), String> {
+    let url = check_url(url)?;
+
+    let host = url.host_str().unwrap_or_default().to_string();
+    if host.parse::<IpAddr>().is_err() && !host.starts_with('[') {
+        actix_rt::task::spawn_blocking(move || resolve_public(&host))
+            .await
+            .map_err(|err| err.to_string())?
+            .map_err(|_| "webhook url must point to a public address".to_string())?;
+    }
+
+    Ok(())
+}
+
+pub async fn deliver_pending(pool: &PgPool) -> Result<(), DatabaseError> {
+    let deliveries =
+        DBWebhookDelivery::claim_due(DELIVERY_BATCH_SIZE, DELIVERY_LEASE_SECONDS as f64, pool)
+            .await?;
+    if deliveries.is_empty() {
+        return Ok(());
+    }
+
+    let max_attempts: i32 = WEBHOOK_MAX_ATTEMPTS()
+        .parse()
+        .expect("`WEBHOOK_MAX_ATTEMPTS` must be a number");
+
+    let client = Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .redirect(Policy::none())
+        .dns_resolver(Arc::new(PublicResolver))
+        .build()
+        .expect("Failed to build webhook HTTP client");
+
+    let results: Vec<Result<(), DatabaseError>> = stream::iter(deliveries)
+        .map(|delivery| deliver(delivery, &client, max_attempts, pool))
+        .buffer_unordered(DELIVERY_CONCURRENCY)
+        .collect()
+        .await;
+
+    results.into_iter().collect()
+}
+
+/// Delivers webhooks as soon as the events behind them are committed instead
+/// of waiting for the next `WEBHOOK_DELIVERY_INTERVAL` tick.
+pub async fn watch_events(pool: PgPool) {
+    let mut events = loop {
+        match EventStream::subscribe(pool.clone(), None).await {
+            Ok(events) => break events,
+            Err(e) => {
+                warn!("Subscribing to events failed: {:?}", e);
+                actix_rt::time::sleep(EVENTS_RETRY_DELAY).await;
+            }
+        }
+    };
+
+    loop {
+        if let Err(e) = events.next_batch().await {
+            warn!("Reading events failed: {:?}", e);
+            actix_rt::time::sleep(EVENTS_RETRY_DELAY).await;
+            continue;
+        }
+
+        if let Err(e) = deliver_pending(&pool).await {
+            warn!("Delivering webhooks failed: {:?}", e);
+        }
+    }
+}
+
+async fn deliver(
+    mut delivery: DBWebhookDelivery,
+    client: &Client,
+    max_attempts: i32,
+    pool: &PgPool,
+) -> Result<(), DatabaseError> {
+    let webhook = match DBWebhook::get_by_id(delivery.webhook_id, pool).await? {
+        Some(webhook) => webhook,
+        None => return Ok(()),
+    };
+
+    let timestamp = Utc::now();
+    let body = json!({
+        "id": delivery.id,
+        "event": delivery.event,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    })
+    .to_string();
+    let signature = sign(&webhook.secret, timestamp.timestamp(), &body);
+
+    let result = match check_url(&webhook.url) {
+        Ok(url) => client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", delivery.id.to_string())
+            .header("X-Webhook-Event", &delivery.event)
+            .header("X-Webhook-Timestamp", timestamp.timestamp())
+            .header("X-Webhook-Signature", format!("sha256={signature}"))
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| err.to_string()),
+        Err(err) => Err(err),
+    };
+
+    delivery.attempts += 1;
+    delivery.last_attempt_at = Some(timestamp);
+    (delivery.response_status, delivery.last_error) = match result {
+        Ok(response) if response.status().is_success() => {
+            (Some(response.status().as_u16().into()), None)
+        }
+        Ok(response) => (
+            Some(response.status().as_u16().into()),
+            Some(format!("Endpoint responded with {}", response.status())),
+        ),
+        Err(err) => (None, Some(err)),
+    };
+
+    delivery.status = if delivery.last_error.is_none() {
+        DBWebhookDeliveryStatus::SUCCEEDED
+    } else if delivery.attempts >= max_attempts {
+        warn!(
+            "Webhook delivery {} failed after {} attempts",
+            delivery.id, delivery.attempts
+        );
+        DBWebhookDeliveryStatus::FAILED
+    } else {
+        delivery.next_attempt_at = timestamp + retry_delay(delivery.attempts);
+        DBWebhookDeliveryStatus::PENDING
+    };
+
+    delivery.update_attempt(pool).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_private_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_address(ip.parse().unwrap()), "{ip}");
+        }
+
+        for ip in ["1.1.1.1", "93.184.216.34", "2606:4700:4700::1111"] {
+            assert!(is_public_address(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[test]
+    fn checks_webhook_urls() {
+        assert!(check_url("https://example.com/hook").is_ok());
+        assert!(check_url("https://1.1.1.1/hook").is_ok());
+
+        assert!(check_url("http://example.com/hook").is_err());
+        assert!(check_url("ftp://example.com").is_err());
+        assert!(check_url("https://localhost/hook").is_err());
+        assert!(check_url("https://api.localhost./hook").is_err());
+        assert!(check_url("https://127.0.0.1:8080/hook").is_err());
+        assert!(check_url("https://169.254.169.254/latest/meta-data").is_err());
+        assert!(check_url("https://[::1]/hook").is_err());
+        assert!(check_url("https://[::ffff:10.0.0.1]/hook").is_err());
+    }
+
+    #[test]
+    fn lease_outlasts_a_batch() {
+        let rounds = (DELIVERY_BATCH_SIZE as u64).div_ceil(DELIVERY_CONCURRENCY as u64);
+        assert!(DELIVERY_LEASE_SECONDS > rounds * DELIVERY_TIMEOUT.as_secs());
+    }
+}
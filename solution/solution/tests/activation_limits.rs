@@ -0,0 +1,71 @@
+mod common;
+
+use chrono::Utc;
+use common::PromoFixture;
+use futures::future::join_all;
+use solution::database::models::{DBActivationUsage, DBPromoActivation};
+use sqlx::{query_scalar, PgPool};
+use uuid::Uuid;
+
+const MAX_PER_DAY: i64 = 5;
+
+/// Activates a COMMON promo the way `Promo::get_code` does, checking the
+/// daily limit against the usage read under the promo lock.
+async fn activate(user_id: Uuid, promo_id: Uuid, pool: &PgPool) -> bool {
+    let mut transaction = pool.begin().await.unwrap();
+    let date = Utc::now();
+
+    let usage = DBActivationUsage::get_locked(user_id, promo_id, date, &mut transaction)
+        .await
+        .unwrap()
+        .unwrap();
+    if usage.today_activations >= MAX_PER_DAY {
+        return false;
+    }
+
+    DBPromoActivation::activate_common(user_id, promo_id, date, &mut transaction)
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+
+    true
+}
+
+#[actix_rt::test]
+async fn concurrent_activations_respect_daily_limit() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+
+    let company_id = common::insert_company(&pool).await;
+    let promo_id = PromoFixture {
+        max_per_day: Some(MAX_PER_DAY as i32),
+        ..PromoFixture::default()
+    }
+    .insert(company_id, &pool)
+    .await;
+
+    let mut users = vec![];
+    for _ in 0..30 {
+        users.push(common::insert_user(&pool, 25, "RU", &[]).await);
+    }
+
+    let activated = join_all(
+        users
+            .iter()
+            .map(|&user_id| activate(user_id, promo_id, &pool)),
+    )
+    .await;
+
+    let count: i64 = query_scalar("SELECT count(*) FROM activations WHERE promo_id = $1")
+        .bind(promo_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        activated.iter().filter(|&&ok| ok).count() as i64,
+        MAX_PER_DAY
+    );
+    assert_eq!(count, MAX_PER_DAY);
+}
@@ -0,0 +1,161 @@
+#![allow(dead_code)]
+
+use std::env;
+
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{postgres::PgPoolOptions, query, PgPool};
+use uuid::Uuid;
+
+/// Connects to the database in `DATABASE_URL` and migrates it. Tests share
+/// the database, so every test creates its own company, users and promos.
+/// Returns `None` when `DATABASE_URL` isn't set and the test should be
+/// skipped.
+pub async fn pool() -> Option<PgPool> {
+    let Ok(url) = env::var("DATABASE_URL") else {
+        eprintln!("`DATABASE_URL` is not set, skipping");
+        return None;
+    };
+
+    let pool = PgPoolOptions::new()
+        .max_connections(32)
+        .connect(&url)
+        .await
+        .expect("Database connection failed");
+
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("An error occurred while running migrations.");
+
+    Some(pool)
+}
+
+pub async fn insert_company(pool: &PgPool) -> Uuid {
+    let id = Uuid::now_v7();
+
+    query("INSERT INTO companies (id, name, email, password_hash) VALUES ($1, $2, $3, '')")
+        .bind(id)
+        .bind(format!("Company {id}"))
+        .bind(format!("{id}@example.com"))
+        .execute(pool)
+        .await
+        .unwrap();
+
+    id
+}
+
+pub async fn insert_user(pool: &PgPool, age: i32, country: &str, interests: &[&str]) -> Uuid {
+    let id = Uuid::now_v7();
+
+    query(
+        "INSERT INTO users (id, name, surname, email, other, password_hash, interests) \
+         VALUES ($1, 'Test', 'User', $2, ROW ($3, $4)::user_target_settings, '', $5)",
+    )
+    .bind(id)
+    .bind(format!("{id}@example.com"))
+    .bind(age)
+    .bind(country)
+    .bind(interests)
+    .execute(pool)
+    .await
+    .unwrap();
+
+    id
+}
+
+pub struct PromoFixture {
+    pub mode: &'static str,
+    pub status: &'static str,
+    pub max_count: i32,
+    pub promo_common: Option<&'static str>,
+    pub country: Option<&'static str>,
+    pub age_from: Option<i32>,
+    pub categories: Option<Vec<&'static str>>,
+    pub active_from: DateTime<Utc>,
+    pub active_until: DateTime<Utc>,
+    pub per_user_limit: Option<i32>,
+    pub cooldown: Option<i32>,
+    pub max_per_day: Option<i32>,
+}
+
+impl Default for PromoFixture {
+    fn default() -> Self {
+        Self {
+            mode: "COMMON",
+            status: "PUBLISHED",
+            max_count: 100,
+            promo_common: Some("COMMON-CODE"),
+            country: None,
+            age_from: None,
+            categories: None,
+            active_from: Utc::now() - Duration::days(1),
+            active_until: Utc::now() + Duration::days(1),
+            per_user_limit: None,
+            cooldown: None,
+            max_per_day: None,
+        }
+    }
+}
+
+impl PromoFixture {
+    pub fn unique() -> Self {
+        Self {
+            mode: "UNIQUE",
+            max_count: 1,
+            promo_common: None,
+            ..Self::default()
+        }
+    }
+
+    pub async fn insert(self, company_id: Uuid, pool: &PgPool) -> Uuid {
+        let id = Uuid::now_v7();
+
+        query(
+            "INSERT INTO promos (id, company_id, description, target, max_count, active_from, \
+             active_until, mode, promo_common, like_count, used_count, comment_count, active, \
+             per_user_limit, cooldown, max_per_day, status) \
+             VALUES ($1, $2, 'Test promo', ROW ($3, NULL, $4, $5)::target, $6, $7, $8, \
+             $9::promo_mode, $10, 0, 0, 0, false, $11, $12, $13, $14::promo_status)",
+        )
+        .bind(id)
+        .bind(company_id)
+        .bind(self.age_from)
+        .bind(self.country)
+        .bind(self.categories)
+        .bind(self.max_count)
+        .bind(self.active_from)
+        .bind(self.active_until)
+        .bind(self.mode)
+        .bind(self.promo_common)
+        .bind(self.per_user_limit)
+        .bind(self.cooldown)
+        .bind(self.max_per_day)
+        .bind(self.status)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        id
+    }
+}
+
+pub async fn insert_codes(promo_id: Uuid, codes: &[String], pool: &PgPool) {
+    query(
+        "INSERT INTO promo_codes (promo_id, code, position) \
+         SELECT $1, code, position FROM unnest($2::text[]) WITH ORDINALITY AS c(code, position)",
+    )
+    .bind(promo_id)
+    .bind(codes)
+    .execute(pool)
+    .await
+    .unwrap();
+}
+
+pub async fn like(user_id: Uuid, promo_id: Uuid, pool: &PgPool) {
+    query("INSERT INTO likes (user_id, promo_id) VALUES ($1, $2)")
+        .bind(user_id)
+        .bind(promo_id)
+        .execute(pool)
+        .await
+        .unwrap();
+}
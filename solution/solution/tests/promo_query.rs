@@ -0,0 +1,212 @@
+mod common;
+
+use std::collections::BTreeSet;
+
+use chrono::{Duration, Utc};
+use common::{like, PromoFixture};
+use solution::{
+    database::models::{
+        DBPromo, DBPromoMode, DBPromoQuery, DBPromoSortField, DBPromoStatus, DBSortOrder,
+    },
+    models::PageRequest,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+struct Fixtures {
+    company_id: Uuid,
+    user_id: Uuid,
+    promos: [Uuid; 4],
+}
+
+/// Four promos that every filter tells apart, see `expected`.
+async fn fixtures(pool: &PgPool) -> Fixtures {
+    let now = Utc::now();
+    let company_id = common::insert_company(pool).await;
+    let user_id = common::insert_user(pool, 25, "RU", &["food"]).await;
+
+    let promos = [
+        PromoFixture {
+            country: Some("RU"),
+            categories: Some(vec!["Food"]),
+            active_from: now - Duration::days(2),
+            active_until: now + Duration::days(2),
+            ..PromoFixture::default()
+        }
+        .insert(company_id, pool)
+        .await,
+        PromoFixture {
+            country: Some("FR"),
+            active_from: now - Duration::days(30),
+            active_until: now - Duration::days(20),
+            ..PromoFixture::unique()
+        }
+        .insert(company_id, pool)
+        .await,
+        PromoFixture {
+            status: "DRAFT",
+            age_from: Some(30),
+            categories: Some(vec!["Travel"]),
+            active_from: now + Duration::days(10),
+            active_until: now + Duration::days(20),
+            ..PromoFixture::default()
+        }
+        .insert(company_id, pool)
+        .await,
+        PromoFixture {
+            country: Some("ru"),
+            ..PromoFixture::default()
+        }
+        .insert(company_id, pool)
+        .await,
+    ];
+
+    like(user_id, promos[0], pool).await;
+
+    Fixtures {
+        company_id,
+        user_id,
+        promos,
+    }
+}
+
+const FILTERS: usize = 9;
+
+/// Applies every filter whose bit is set along with the company filter, and
+/// returns the indexes of the fixtures each of them keeps on its own.
+fn filtered(fixtures: &Fixtures, filters: u32) -> (DBPromoQuery, BTreeSet<usize>) {
+    let now = Utc::now();
+    let enabled = |filter: usize| filters & (1 << filter) != 0;
+    let mut expected: BTreeSet<usize> = (0..4).collect();
+    let mut keep = |indexes: &[usize]| expected.retain(|i| indexes.contains(i));
+
+    let mut query = DBPromoQuery::new().company(fixtures.company_id);
+    if enabled(0) {
+        query = query.targeting(fixtures.user_id);
+        keep(&[0, 3]);
+    }
+    if enabled(1) {
+        query = query.liked_by(fixtures.user_id);
+        keep(&[0]);
+    }
+    query = query.countries(enabled(2).then(|| vec!["RU".to_string()]));
+    if enabled(2) {
+        keep(&[0, 2, 3]);
+    }
+    query = query.category(enabled(3).then(|| "FOOD".to_string()));
+    if enabled(3) {
+        keep(&[0]);
+    }
+    query = query.active(enabled(4).then_some(true));
+    if enabled(4) {
+        keep(&[0, 3]);
+    }
+    query = query.mode(enabled(5).then_some(DBPromoMode::UNIQUE));
+    if enabled(5) {
+        keep(&[1]);
+    }
+    query = query.status(enabled(6).then_some(DBPromoStatus::PUBLISHED));
+    if enabled(6) {
+        keep(&[0, 1, 3]);
+    }
+    query = query.active_between(
+        enabled(7).then(|| now + Duration::days(5)),
+        enabled(8).then(|| now - Duration::days(10)),
+    );
+    if enabled(7) {
+        keep(&[2]);
+    }
+    if enabled(8) {
+        keep(&[1]);
+    }
+
+    (query, expected)
+}
+
+fn sort_key(promo: &DBPromo, sort_by: DBPromoSortField) -> (Option<chrono::DateTime<Utc>>, Uuid) {
+    match sort_by {
+        DBPromoSortField::Id => (None, promo.id),
+        DBPromoSortField::ActiveFrom => (Some(promo.active_from), promo.id),
+        DBPromoSortField::ActiveUntil => (Some(promo.active_until), promo.id),
+    }
+}
+
+#[actix_rt::test]
+async fn filters_match_fixtures_in_every_combination() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let fixtures = fixtures(&pool).await;
+
+    for filters in 0..1 << FILTERS {
+        let (query, expected) = filtered(&fixtures, filters);
+        let expected: BTreeSet<Uuid> = expected.iter().map(|&i| fixtures.promos[i]).collect();
+
+        let page = PageRequest {
+            limit: 10,
+            offset: 0,
+            cursor: None,
+            with_total: true,
+        };
+        let page = query.fetch_page(&page, &pool).await.unwrap();
+
+        let found: BTreeSet<Uuid> = page.items.iter().map(|promo| promo.id).collect();
+        assert_eq!(found, expected, "filters {filters:#011b}");
+        assert_eq!(
+            page.total,
+            Some(expected.len() as i64),
+            "filters {filters:#011b}"
+        );
+    }
+}
+
+#[actix_rt::test]
+async fn keyset_pages_follow_sort_order_in_every_combination() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+    let fixtures = fixtures(&pool).await;
+
+    for filters in 0..1 << FILTERS {
+        for sort_by in [
+            DBPromoSortField::Id,
+            DBPromoSortField::ActiveFrom,
+            DBPromoSortField::ActiveUntil,
+        ] {
+            for order in [DBSortOrder::Asc, DBSortOrder::Desc] {
+                let (query, _) = filtered(&fixtures, filters);
+                let query = query.sort(sort_by, order);
+
+                let mut expected = query.fetch_all(10, 0, &pool).await.unwrap();
+                expected.sort_by_key(|promo| sort_key(promo, sort_by));
+                if order == DBSortOrder::Desc {
+                    expected.reverse();
+                }
+                let expected: Vec<Uuid> = expected.iter().map(|promo| promo.id).collect();
+
+                let mut found = vec![];
+                let mut cursor = None;
+                loop {
+                    let page = PageRequest {
+                        limit: 1,
+                        offset: 0,
+                        cursor,
+                        with_total: false,
+                    };
+                    let page = query.fetch_page(&page, &pool).await.unwrap();
+                    found.extend(page.items.iter().map(|promo| promo.id));
+
+                    match page.next_cursor {
+                        Some(next) => cursor = Some(next),
+                        None => break,
+                    }
+                }
+
+                assert_eq!(
+                    found, expected,
+                    "filters {filters:#011b}, {sort_by:?} {order:?}"
+                );
+            }
+        }
+    }
+}
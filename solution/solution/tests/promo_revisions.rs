@@ -0,0 +1,52 @@
+mod common;
+
+use chrono::Utc;
+use common::PromoFixture;
+use futures::future::join_all;
+use serde_json::json;
+use solution::database::models::{DBPromo, DBPromoRevision, DBRevisionAuthor};
+use sqlx::query_scalar;
+use uuid::Uuid;
+
+#[actix_rt::test]
+async fn concurrent_revisions_get_consecutive_numbers() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+
+    let company_id = common::insert_company(&pool).await;
+    let promo_id = PromoFixture::default().insert(company_id, &pool).await;
+
+    join_all((0..20).map(|i| {
+        let pool = pool.clone();
+        async move {
+            let mut transaction = pool.begin().await.unwrap();
+            assert!(DBPromo::lock(promo_id, &mut transaction).await.unwrap());
+
+            DBPromoRevision {
+                id: Uuid::now_v7(),
+                promo_id,
+                revision: 0,
+                author: DBRevisionAuthor::COMPANY,
+                member_id: None,
+                restored_from: None,
+                changes: json!({ "max_count": i }),
+                snapshot: json!({}),
+                created_at: Utc::now(),
+            }
+            .insert(&mut transaction)
+            .await
+            .unwrap();
+            transaction.commit().await.unwrap();
+        }
+    }))
+    .await;
+
+    let revisions: Vec<i32> =
+        query_scalar("SELECT revision FROM promo_revisions WHERE promo_id = $1 ORDER BY revision")
+            .bind(promo_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(revisions, (1..=20).collect::<Vec<_>>());
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use chrono::Utc;
+use common::PromoFixture;
+use solution::database::models::DBPromoActivation;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn activate_unique(user_id: Uuid, promo_id: Uuid, pool: &PgPool) -> String {
+    let mut transaction = pool.begin().await.unwrap();
+    let activation =
+        DBPromoActivation::activate_unique(user_id, promo_id, Utc::now(), &mut transaction)
+            .await
+            .unwrap()
+            .unwrap();
+    transaction.commit().await.unwrap();
+
+    activation.promo
+}
+
+async fn redeem(promo_id: Uuid, code: &str, pool: &PgPool) -> Option<DBPromoActivation> {
+    let mut transaction = pool.begin().await.unwrap();
+    let activation = DBPromoActivation::redeem(promo_id, code, Utc::now(), &mut transaction)
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+
+    activation
+}
+
+#[actix_rt::test]
+async fn only_unique_codes_are_redeemable() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+
+    let company_id = common::insert_company(&pool).await;
+    let user_id = common::insert_user(&pool, 25, "RU", &[]).await;
+
+    let common_id = PromoFixture::default().insert(company_id, &pool).await;
+    let mut transaction = pool.begin().await.unwrap();
+    DBPromoActivation::activate_common(user_id, common_id, Utc::now(), &mut transaction)
+        .await
+        .unwrap();
+    transaction.commit().await.unwrap();
+
+    let found = DBPromoActivation::get_by_code(company_id, "COMMON-CODE", None, &pool)
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+
+    let unique_id = PromoFixture::unique().insert(company_id, &pool).await;
+    common::insert_codes(unique_id, &[format!("{unique_id}")], &pool).await;
+    let code = activate_unique(user_id, unique_id, &pool).await;
+
+    let other_company = common::insert_company(&pool).await;
+    let found = DBPromoActivation::get_by_code(other_company, &code, None, &pool)
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+
+    let found = DBPromoActivation::get_by_code(company_id, &code, None, &pool)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].user_id, user_id);
+    assert_eq!(found[0].promo_id, unique_id);
+
+    let redeemed = redeem(unique_id, &code, &pool).await.unwrap();
+    assert!(redeemed.redeemed_at.is_some());
+    assert!(redeem(unique_id, &code, &pool).await.is_none());
+
+    let found = DBPromoActivation::get_by_code(company_id, &code, None, &pool)
+        .await
+        .unwrap();
+    assert!(found[0].redeemed_at.is_some());
+}
+
+#[actix_rt::test]
+async fn codes_shared_by_promos_are_told_apart_by_promo() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+
+    let company_id = common::insert_company(&pool).await;
+    let code = format!("{company_id}");
+
+    let mut promos = vec![];
+    for _ in 0..2 {
+        let promo_id = PromoFixture::unique().insert(company_id, &pool).await;
+        common::insert_codes(promo_id, std::slice::from_ref(&code), &pool).await;
+        let user_id = common::insert_user(&pool, 25, "RU", &[]).await;
+        activate_unique(user_id, promo_id, &pool).await;
+        promos.push((promo_id, user_id));
+    }
+
+    let found = DBPromoActivation::get_by_code(company_id, &code, None, &pool)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 2);
+
+    for (promo_id, user_id) in promos {
+        let found = DBPromoActivation::get_by_code(company_id, &code, Some(promo_id), &pool)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].user_id, user_id);
+    }
+}
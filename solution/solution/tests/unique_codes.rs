@@ -0,0 +1,114 @@
+mod common;
+
+use std::collections::HashSet;
+
+use chrono::Utc;
+use common::PromoFixture;
+use futures::future::join_all;
+use solution::database::models::{DBPromo, DBPromoActivation, DBPromoCode};
+use sqlx::{query_scalar, PgPool};
+use uuid::Uuid;
+
+const CODES: usize = 10;
+
+/// Issues a code the way `Promo::get_code` does for UNIQUE promos. Returns
+/// `None` when the pool is exhausted.
+async fn activate(user_id: Uuid, promo_id: Uuid, pool: &PgPool) -> Option<String> {
+    let mut transaction = pool.begin().await.unwrap();
+
+    let activation =
+        DBPromoActivation::activate_unique(user_id, promo_id, Utc::now(), &mut transaction)
+            .await
+            .unwrap()?;
+    transaction.commit().await.unwrap();
+
+    Some(activation.promo)
+}
+
+#[actix_rt::test]
+async fn concurrent_activations_issue_each_code_once() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+
+    let company_id = common::insert_company(&pool).await;
+    let promo_id = PromoFixture::unique().insert(company_id, &pool).await;
+    let codes: Vec<String> = (0..CODES).map(|i| format!("{promo_id}-{i}")).collect();
+    common::insert_codes(promo_id, &codes, &pool).await;
+
+    let mut users = vec![];
+    for _ in 0..30 {
+        users.push(common::insert_user(&pool, 25, "RU", &[]).await);
+    }
+
+    // Every user asks twice at once and gets the same code both times.
+    let results = join_all(
+        users
+            .iter()
+            .chain(users.iter())
+            .map(|&user_id| activate(user_id, promo_id, &pool)),
+    )
+    .await;
+    let (first, second) = results.split_at(users.len());
+    assert_eq!(first, second);
+
+    let issued: Vec<&String> = first.iter().flatten().collect();
+    assert_eq!(issued.len(), CODES);
+    assert_eq!(
+        issued.into_iter().collect::<HashSet<_>>(),
+        codes.iter().collect::<HashSet<_>>()
+    );
+
+    let activations: i64 = query_scalar("SELECT count(*) FROM activations WHERE promo_id = $1")
+        .bind(promo_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let holders: i64 =
+        query_scalar("SELECT count(DISTINCT user_id) FROM activations WHERE promo_id = $1")
+            .bind(promo_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_eq!(activations, CODES as i64);
+    assert_eq!(holders, CODES as i64);
+
+    let promo = DBPromo::get_by_id(promo_id, &pool).await.unwrap().unwrap();
+    assert_eq!(promo.used_count, CODES as i32);
+    assert_eq!(promo.unique_count, CODES as i64);
+    assert_eq!(promo.unique_available, 0);
+    assert!(!promo.active);
+}
+
+#[actix_rt::test]
+async fn concurrent_uploads_number_codes_in_order() {
+    let Some(pool) = common::pool().await else {
+        return;
+    };
+
+    let company_id = common::insert_company(&pool).await;
+    let promo_id = PromoFixture::unique().insert(company_id, &pool).await;
+
+    join_all((0..10).map(|upload| {
+        let pool = pool.clone();
+        async move {
+            let codes: Vec<String> = (0..100).map(|i| format!("{upload}-{i}")).collect();
+
+            let mut transaction = pool.begin().await.unwrap();
+            assert!(DBPromo::lock(promo_id, &mut transaction).await.unwrap());
+            DBPromoCode::insert_many(promo_id, &codes, &mut transaction)
+                .await
+                .unwrap();
+            transaction.commit().await.unwrap();
+        }
+    }))
+    .await;
+
+    let positions: Vec<i64> =
+        query_scalar("SELECT position FROM promo_codes WHERE promo_id = $1 ORDER BY position")
+            .bind(promo_id)
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(positions, (1..=1000).collect::<Vec<_>>());
+}